@@ -16,9 +16,11 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  ******************************************************************************/
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
 
+use derive_more::{Display, Error};
 use heck::ToTitleCase;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -27,6 +29,86 @@ use crate::device::{ChannelStatus, DeviceInfo, LightingMode, Status, TempStatus}
 
 type StatusMap = HashMap<String, String>;
 
+/// A declarative allow-list or deny-list of sensor/channel names, matched literally or as a
+/// regex, so noisy or irrelevant readings (phantom probes, unused fan headers reporting 0 RPM)
+/// can be suppressed before they reach the frontend. Mirrors the list/ignore/regex/case/
+/// whole-word filtering model used for device-level temp filtering.
+///
+/// Whether it behaves as an allow-list or a deny-list is controlled by `is_list_ignored`: with
+/// `is_list_ignored = false`, only names matching `list` are kept (an allow-list); with
+/// `is_list_ignored = true`, names matching `list` are hidden and everything else is kept (a
+/// deny-list). An empty `list` matches nothing, so the default `SensorFilter` is a no-op.
+#[derive(Debug, Default)]
+pub struct SensorFilter {
+    /// Whether `list` names things to hide (`true`) or things to keep (`false`).
+    pub is_list_ignored: bool,
+
+    /// The names or patterns to match against.
+    pub list: Vec<String>,
+
+    /// Treat each `list` entry as a regular expression instead of a literal string.
+    pub regex: bool,
+
+    /// Match case-sensitively. Has no effect on a `regex` entry that sets its own flags.
+    pub case_sensitive: bool,
+
+    /// Anchor each pattern to match the entire name rather than a substring.
+    pub whole_word: bool,
+
+    /// Lazily compiled `list` patterns, built once on first use and reused for every
+    /// `TempStatus`/`ChannelStatus` a device reports, rather than recompiling per sensor.
+    compiled: RefCell<Option<Vec<Regex>>>,
+}
+
+impl SensorFilter {
+    /// Builds the effective regex pattern for one `list` entry, applying `regex`, `whole_word`,
+    /// and `case_sensitive` in that order.
+    fn pattern_for(&self, entry: &str) -> String {
+        let body = if self.regex {
+            entry.to_string()
+        } else {
+            regex::escape(entry)
+        };
+        let body = if self.whole_word {
+            format!("^(?:{body})$")
+        } else {
+            body
+        };
+        if self.case_sensitive {
+            body
+        } else {
+            format!("(?i){body}")
+        }
+    }
+
+    fn compiled_patterns(&self) -> Vec<Regex> {
+        if self.compiled.borrow().is_none() {
+            let patterns = self
+                .list
+                .iter()
+                .filter_map(|entry| Regex::new(&self.pattern_for(entry)).ok())
+                .collect();
+            self.compiled.replace(Some(patterns));
+        }
+        self.compiled.borrow().clone().unwrap()
+    }
+
+    /// Returns whether `candidate` (a sensor's `name`, or `frontend_name` when matching the
+    /// user-facing label makes more sense) should be dropped: a match is dropped when
+    /// `is_list_ignored` is `true`, and a non-match is dropped when it is `false`. An empty
+    /// `list` short-circuits to `false`, so an unconfigured `SensorFilter` hides nothing.
+    pub fn is_ignored(&self, candidate: &str) -> bool {
+        if self.list.is_empty() {
+            return false;
+        }
+        let matches = self
+            .compiled_patterns()
+            .iter()
+            .any(|pattern| pattern.is_match(candidate));
+        self.is_list_ignored == matches
+    }
+}
+
 fn parse_float(value: &String) -> Option<f64> {
     value.parse::<f64>().ok()
 }
@@ -35,6 +117,81 @@ fn parse_u32(value: &String) -> Option<u32> {
     value.parse::<u32>().ok()
 }
 
+/// Strips a dotted version component down to its leading digits, so trailing build suffixes
+/// like `"0-beta"` still parse as `0` instead of failing outright.
+fn leading_digits(component: &str) -> String {
+    component.chars().take_while(char::is_ascii_digit).collect()
+}
+
+/// A firmware version parsed into its `(major, minor, patch)` triple, matching how NZXT devices
+/// expose firmware as a `(major, minor, revision)` triple, alongside the original string so
+/// non-numeric versions still round-trip for display.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FirmwareVersion {
+    pub major: u8,
+    pub minor: u16,
+    pub patch: u8,
+    pub raw: String,
+}
+
+/// The allowed duty/speed window for a channel, used by `validate_duty` to reject values the
+/// firmware would otherwise silently ignore or clamp unpredictably.
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelSpeedRange {
+    pub min: u8,
+    pub max: u8,
+}
+
+impl Default for ChannelSpeedRange {
+    fn default() -> Self {
+        Self { min: 0, max: 100 }
+    }
+}
+
+#[derive(Debug, Clone, Display, Error)]
+pub enum DeviceSupportError {
+    #[display(fmt = "Duty {given} for channel \"{channel}\" is out of range {min}-{max}")]
+    SpeedOutOfRange {
+        channel: String,
+        given: u8,
+        min: u8,
+        max: u8,
+    },
+}
+
+impl TempStatus {
+    /// Whether the current reading has reached its critical threshold, following the Linux
+    /// hwmon convention of pairing a current reading with optional max/crit companions.
+    /// `false` when no critical threshold was reported for this sensor.
+    pub fn is_critical(&self) -> bool {
+        self.critical.is_some_and(|critical| self.temp >= critical)
+    }
+}
+
+/// The physical unit a `MetricStatus` value is expressed in, so a scalar reading that isn't a
+/// temperature (acoustic noise, voltage, power) can be told apart without guessing from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricUnit {
+    Decibel,
+    Celsius,
+    Rpm,
+    Percent,
+    Volt,
+    Watt,
+}
+
+/// A non-temperature scalar telemetry reading (acoustic noise, voltage, power, ...). Exists
+/// because `TempStatus`'s current/max/critical shape is specific to temperatures, and liquidctl
+/// drivers report a growing variety of other scalars that shouldn't have to masquerade as one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricStatus {
+    pub name: String,
+    pub value: f64,
+    pub unit: MetricUnit,
+    pub frontend_name: String,
+    pub external_name: String,
+}
+
 /// It is a general purpose trait and each supported device struc must implement this trait.
 /// Many of the default methods will cover all use cases and it is advisable to override them
 /// for increase efficiency and performance.
@@ -46,11 +203,16 @@ pub trait DeviceSupport: Debug + Sync + Send {
     // todo: in python this is an *args: Any parameter... that won't fly here.
     fn get_filtered_color_channel_modes(&self) -> Vec<LightingMode>;
 
-    fn extract_status(&self, status_map: &StatusMap, device_id: &u8) -> Status {
+    fn extract_status(&self, status_map: &StatusMap, device_id: &u8, filter: Option<&SensorFilter>) -> Status {
+        let temps = self.get_temperatures(status_map, device_id, filter);
+        let has_critical_temp = temps.iter().any(TempStatus::is_critical);
         Status {
             firmware_version: self.get_firmware_ver(status_map),
-            temps: self.get_temperatures(status_map, device_id),
-            channels: self.get_channel_statuses(status_map, device_id),
+            firmware_version_parsed: self.parse_firmware_ver(status_map),
+            has_critical_temp,
+            temps,
+            channels: self.get_channel_statuses(status_map, device_id, filter),
+            metrics: self.get_metrics(status_map, device_id),
             ..Default::default()
         }
     }
@@ -59,18 +221,35 @@ pub trait DeviceSupport: Debug + Sync + Send {
         status_map.get("firmware version").cloned()
     }
 
+    /// Parses the liquidctl "firmware version" string into a `FirmwareVersion`, splitting on
+    /// `.` and tolerating a missing patch component (defaults to `0`) and trailing build
+    /// suffixes (e.g. `-beta`) on any component. Non-numeric strings like `"whatever"` still
+    /// round-trip: `raw` keeps the original text while `major`/`minor`/`patch` default to `0`.
+    fn parse_firmware_ver(&self, status_map: &StatusMap) -> Option<FirmwareVersion> {
+        let raw = self.get_firmware_ver(status_map)?;
+        let mut components = raw.split('.');
+        let major = components.next().map(leading_digits).and_then(|d| d.parse().ok()).unwrap_or(0);
+        let minor = components.next().map(leading_digits).and_then(|d| d.parse().ok()).unwrap_or(0);
+        let patch = components.next().map(leading_digits).and_then(|d| d.parse().ok()).unwrap_or(0);
+        Some(FirmwareVersion { major, minor, patch, raw })
+    }
+
 
     /// It's possible to override this method and use only the needed sub-functions per device
     fn get_temperatures(&self,
                         status_map: &StatusMap,
                         device_id: &u8,
+                        filter: Option<&SensorFilter>,
     ) -> Vec<TempStatus> {
         let mut temps = vec![];
         self.add_liquid_temp(status_map, &mut temps, device_id);
         self.add_water_temp(status_map, &mut temps, device_id);
         self.add_temp(status_map, &mut temps, device_id);
         self.add_temp_probes(status_map, &mut temps, device_id);
-        self.add_noise_level(status_map, &mut temps, device_id);
+        self.add_noise_level_as_temp(&self.get_metrics(status_map, device_id), &mut temps);
+        if let Some(filter) = filter {
+            temps.retain(|temp| !filter.is_ignored(&temp.name));
+        }
         temps
     }
 
@@ -81,6 +260,8 @@ pub trait DeviceSupport: Debug + Sync + Send {
             temps.push(TempStatus {
                 name: "liquid".to_string(),
                 temp,
+                max: status_map.get("liquid temperature max").and_then(parse_float),
+                critical: status_map.get("liquid temperature crit").and_then(parse_float),
                 frontend_name: "Liquid".to_string(),
                 external_name: format!("LC#{} Liquid", device_id),
             })
@@ -94,6 +275,8 @@ pub trait DeviceSupport: Debug + Sync + Send {
             temps.push(TempStatus {
                 name: "water".to_string(),
                 temp,
+                max: status_map.get("water temperature max").and_then(parse_float),
+                critical: status_map.get("water temperature crit").and_then(parse_float),
                 frontend_name: "Water".to_string(),
                 external_name: format!("LC#{} Water", device_id),
             })
@@ -107,6 +290,8 @@ pub trait DeviceSupport: Debug + Sync + Send {
             temps.push(TempStatus {
                 name: "temp".to_string(),
                 temp,
+                max: status_map.get("temperature max").and_then(parse_float),
+                critical: status_map.get("temperature crit").and_then(parse_float),
                 frontend_name: "Temp".to_string(),
                 external_name: format!("LC#{} Temp", device_id),
             })
@@ -125,6 +310,8 @@ pub trait DeviceSupport: Debug + Sync + Send {
                         let name = format!("temp{}", probe_number.as_str());
                         temps.push(TempStatus {
                             temp,
+                            max: status_map.get(&format!("{probe_name} max")).and_then(parse_float),
+                            critical: status_map.get(&format!("{probe_name} crit")).and_then(parse_float),
                             frontend_name: name.to_title_case(),
                             external_name: format!("LC#{} {}", device_id, name.to_title_case()),
                             name,
@@ -135,25 +322,54 @@ pub trait DeviceSupport: Debug + Sync + Send {
         }
     }
 
-    fn add_noise_level(&self, status_map: &StatusMap, temps: &mut Vec<TempStatus>, device_id: &u8) {
+    /// It's possible to override this method and use only the needed sub-functions per device
+    fn get_metrics(&self, status_map: &StatusMap, device_id: &u8) -> Vec<MetricStatus> {
+        let mut metrics = vec![];
+        self.add_noise_level(status_map, &mut metrics, device_id);
+        metrics
+    }
+
+    fn add_noise_level(&self, status_map: &StatusMap, metrics: &mut Vec<MetricStatus>, device_id: &u8) {
         let noise_lvl = status_map.get("noise level")
             .and_then(parse_float);
         if let Some(noise) = noise_lvl {
-            temps.push(TempStatus {
+            metrics.push(MetricStatus {
                 name: "noise".to_string(),
-                temp: noise,
+                value: noise,
+                unit: MetricUnit::Decibel,
                 frontend_name: "Noise dB".to_string(),
                 external_name: format!("LC#{} Noise dB", device_id),
             })
         }
     }
 
+    /// Migration shim: surfaces decibel metrics as `TempStatus` entries so existing `temps`
+    /// consumers (filters, thresholds, graphs) keep seeing noise level until they switch over
+    /// to reading it from `Status::metrics` instead. No max/critical thresholds apply to it.
+    fn add_noise_level_as_temp(&self, metrics: &[MetricStatus], temps: &mut Vec<TempStatus>) {
+        for metric in metrics {
+            if metric.unit == MetricUnit::Decibel {
+                temps.push(TempStatus {
+                    name: metric.name.clone(),
+                    temp: metric.value,
+                    max: None,
+                    critical: None,
+                    frontend_name: metric.frontend_name.clone(),
+                    external_name: metric.external_name.clone(),
+                })
+            }
+        }
+    }
+
     /// It's possible to override this method and use only the needed sub-functions per device
-    fn get_channel_statuses(&self, status_map: &StatusMap, device_id: &u8) -> Vec<ChannelStatus> {
+    fn get_channel_statuses(&self, status_map: &StatusMap, device_id: &u8, filter: Option<&SensorFilter>) -> Vec<ChannelStatus> {
         let mut channel_statuses = vec![];
         self.add_single_fan_status(status_map, &mut channel_statuses);
         self.add_single_pump_status(status_map, &mut channel_statuses);
         self.add_multiple_fans_status(status_map, &mut channel_statuses);
+        if let Some(filter) = filter {
+            channel_statuses.retain(|channel| !filter.is_ignored(&channel.name));
+        }
         channel_statuses
     }
 
@@ -234,6 +450,33 @@ pub trait DeviceSupport: Debug + Sync + Send {
     fn channel_to_frontend_name(&self, lighting_channel: &String) -> String {
         lighting_channel.replace("-", " ").replace("_", " ").to_title_case()
     }
+
+    /// The allowed duty/speed window for `channel_name` on this device. Defaults to the full
+    /// 0-100 range; devices with real hardware limits (e.g. a Kraken pump that stalls below 60%)
+    /// should override this per channel.
+    fn speed_range_for(&self, _channel_name: &str) -> ChannelSpeedRange {
+        ChannelSpeedRange::default()
+    }
+
+    /// Validates `duty` against `speed_range_for(channel_name)` before it's sent to the device,
+    /// since the firmware often silently ignores (rather than rejects) an out-of-range duty.
+    /// When `clamp` is `true`, an out-of-range duty is pulled back into bounds instead of
+    /// erroring.
+    fn validate_duty(&self, channel_name: &str, duty: u8, clamp: bool) -> Result<u8, DeviceSupportError> {
+        let range = self.speed_range_for(channel_name);
+        if duty >= range.min && duty <= range.max {
+            return Ok(duty);
+        }
+        if clamp {
+            return Ok(duty.clamp(range.min, range.max));
+        }
+        Err(DeviceSupportError::SpeedOutOfRange {
+            channel: channel_name.to_string(),
+            given: duty,
+            min: range.min,
+            max: range.max,
+        })
+    }
 }
 
 /// Support for the Liquidctl KrakenX3 Driver
@@ -254,6 +497,15 @@ impl DeviceSupport for KrakenX3Support {
     fn get_filtered_color_channel_modes(&self) -> Vec<LightingMode> {
         todo!()
     }
+
+    /// The Kraken X3's pump stalls below 60% and is otherwise fine up to 100%; its fan follows
+    /// the default 0-100 range.
+    fn speed_range_for(&self, channel_name: &str) -> ChannelSpeedRange {
+        match channel_name {
+            "pump" => ChannelSpeedRange { min: 60, max: 100 },
+            _ => ChannelSpeedRange::default(),
+        }
+    }
 }
 
 /// Support for the Liquidctl SmartDevice2 Driver
@@ -274,6 +526,11 @@ impl DeviceSupport for SmartDevice2Support {
     fn get_filtered_color_channel_modes(&self) -> Vec<LightingMode> {
         todo!()
     }
+
+    /// The Smart Device 2's fans stall below 25%.
+    fn speed_range_for(&self, _channel_name: &str) -> ChannelSpeedRange {
+        ChannelSpeedRange { min: 25, max: 100 }
+    }
 }
 
 /// Tests
@@ -283,7 +540,7 @@ mod tests {
 
     fn assert_temp_status_vector_contents_eq(device_support: KrakenX3Support, device_id: &u8, given_expected: Vec<(HashMap<String, String>, Vec<TempStatus>)>) {
         for (given, expected) in given_expected {
-            let result = device_support.get_temperatures(&given, &device_id);
+            let result = device_support.get_temperatures(&given, &device_id, None);
             assert!(
                 expected.iter().all(|temp_status| result.contains(&temp_status))
             );
@@ -316,6 +573,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_firmware_ver() {
+        let device_support = KrakenX3Support::new();
+        let given_expected = vec![
+            (
+                HashMap::from([("firmware version".to_string(), "1.2.3".to_string())]),
+                Some(FirmwareVersion { major: 1, minor: 2, patch: 3, raw: "1.2.3".to_string() }),
+            ),
+            (
+                HashMap::from([("firmware version".to_string(), "1.2".to_string())]),
+                Some(FirmwareVersion { major: 1, minor: 2, patch: 0, raw: "1.2".to_string() }),
+            ),
+            (
+                HashMap::from([("firmware version".to_string(), "1.2.3-beta".to_string())]),
+                Some(FirmwareVersion { major: 1, minor: 2, patch: 3, raw: "1.2.3-beta".to_string() }),
+            ),
+            (
+                HashMap::from([("firmware version".to_string(), "whatever".to_string())]),
+                Some(FirmwareVersion { major: 0, minor: 0, patch: 0, raw: "whatever".to_string() }),
+            ),
+            (HashMap::from([("firmware".to_string(), "1.2.3".to_string())]), None),
+        ];
+        for (given, expected) in given_expected {
+            assert_eq!(device_support.parse_firmware_ver(&given), expected);
+        }
+    }
+
     #[test]
     fn get_temperatures_fail() {
         let device_support = KrakenX3Support::new();
@@ -329,7 +613,7 @@ mod tests {
             (HashMap::from([("some other temperature".to_string(), temp.clone())]), vec![]),
         ];
         for (given, expected) in given_expected {
-            let result = device_support.get_temperatures(&given, &device_id);
+            let result = device_support.get_temperatures(&given, &device_id, None);
             assert!(
                 expected.iter().all(|temp_status| !result.contains(&temp_status))
             );
@@ -350,6 +634,8 @@ mod tests {
                 vec![TempStatus {
                     name: "liquid".to_string(),
                     temp: temp.parse().unwrap(),
+                    max: None,
+                    critical: None,
                     frontend_name: "Liquid".to_string(),
                     external_name: "LC#1 Liquid".to_string(),
                 }]
@@ -370,6 +656,8 @@ mod tests {
                 vec![TempStatus {
                     name: "water".to_string(),
                     temp: temp.parse().unwrap(),
+                    max: None,
+                    critical: None,
                     frontend_name: "Water".to_string(),
                     external_name: "LC#1 Water".to_string(),
                 }]
@@ -389,6 +677,8 @@ mod tests {
                 vec![TempStatus {
                     name: "temp".to_string(),
                     temp: temp.parse().unwrap(),
+                    max: None,
+                    critical: None,
                     frontend_name: "Temp".to_string(),
                     external_name: "LC#1 Temp".to_string(),
                 }]
@@ -414,18 +704,24 @@ mod tests {
                     TempStatus {
                         name: "temp1".to_string(),
                         temp: temp.parse().unwrap(),
+                        max: None,
+                        critical: None,
                         frontend_name: "Temp1".to_string(),
                         external_name: "LC#1 Temp1".to_string(),
                     },
                     TempStatus {
                         name: "temp2".to_string(),
                         temp: temp.parse().unwrap(),
+                        max: None,
+                        critical: None,
                         frontend_name: "Temp2".to_string(),
                         external_name: "LC#1 Temp2".to_string(),
                     },
                     TempStatus {
                         name: "temp3".to_string(),
                         temp: temp.parse().unwrap(),
+                        max: None,
+                        critical: None,
                         frontend_name: "Temp3".to_string(),
                         external_name: "LC#1 Temp3".to_string(),
                     },
@@ -436,7 +732,7 @@ mod tests {
     }
 
     #[test]
-    fn add_noise_level() {
+    fn add_noise_level_shimmed_into_temps() {
         let device_support = KrakenX3Support::new();
         let device_id: u8 = 1;
         let noise_lvl = "33.3".to_string();
@@ -446,6 +742,8 @@ mod tests {
                 vec![TempStatus {
                     name: "noise".to_string(),
                     temp: noise_lvl.parse().unwrap(),
+                    max: None,
+                    critical: None,
                     frontend_name: "Noise dB".to_string(),
                     external_name: "LC#1 Noise dB".to_string(),
                 }]
@@ -454,9 +752,24 @@ mod tests {
         assert_temp_status_vector_contents_eq(device_support, &device_id, given_expected)
     }
 
+    #[test]
+    fn add_noise_level_reports_as_metric() {
+        let device_support = KrakenX3Support::new();
+        let device_id: u8 = 1;
+        let status_map = HashMap::from([("noise level".to_string(), "33.3".to_string())]);
+        let metrics = device_support.get_metrics(&status_map, &device_id);
+        assert_eq!(metrics, vec![MetricStatus {
+            name: "noise".to_string(),
+            value: 33.3,
+            unit: MetricUnit::Decibel,
+            frontend_name: "Noise dB".to_string(),
+            external_name: "LC#1 Noise dB".to_string(),
+        }]);
+    }
+
     fn assert_channel_statuses_eq(device_support: KrakenX3Support, device_id: &u8, given_expected: Vec<(HashMap<String, String>, Vec<ChannelStatus>)>) {
         for (given, expected) in given_expected {
-            let result = device_support.get_channel_statuses(&given, &device_id);
+            let result = device_support.get_channel_statuses(&given, &device_id, None);
             assert!(
                 expected.iter().all(|temp_status| result.contains(&temp_status))
             );
@@ -658,4 +971,136 @@ mod tests {
         ];
         assert_channel_statuses_eq(device_support, &device_id, given_expected);
     }
+
+    #[test]
+    fn sensor_filter_allow_list_literal() {
+        let filter = SensorFilter {
+            list: vec!["liquid".to_string()],
+            ..Default::default()
+        };
+        assert!(!filter.is_ignored("liquid"));
+        assert!(filter.is_ignored("noise"));
+    }
+
+    #[test]
+    fn sensor_filter_deny_list_literal() {
+        let filter = SensorFilter {
+            is_list_ignored: true,
+            list: vec!["noise".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.is_ignored("noise"));
+        assert!(!filter.is_ignored("liquid"));
+    }
+
+    #[test]
+    fn sensor_filter_regex_whole_word() {
+        let filter = SensorFilter {
+            is_list_ignored: true,
+            list: vec![r"temp\d+".to_string()],
+            regex: true,
+            whole_word: true,
+            ..Default::default()
+        };
+        assert!(filter.is_ignored("temp7"));
+        assert!(!filter.is_ignored("temp7other"));
+    }
+
+    #[test]
+    fn get_temperatures_applies_filter() {
+        let device_support = KrakenX3Support::new();
+        let device_id: u8 = 1;
+        let status_map = HashMap::from([
+            ("liquid temperature".to_string(), "33.3".to_string()),
+            ("noise level".to_string(), "33.3".to_string()),
+        ]);
+        let filter = SensorFilter {
+            is_list_ignored: true,
+            list: vec!["noise".to_string()],
+            ..Default::default()
+        };
+        let result = device_support.get_temperatures(&status_map, &device_id, Some(&filter));
+        assert!(result.iter().any(|temp| temp.name == "liquid"));
+        assert!(result.iter().all(|temp| temp.name != "noise"));
+    }
+
+    #[test]
+    fn validate_duty_kraken_pump_range() {
+        let device_support = KrakenX3Support::new();
+        assert_eq!(device_support.validate_duty("pump", 80, false), Ok(80));
+        assert!(device_support.validate_duty("pump", 50, false).is_err());
+        assert_eq!(device_support.validate_duty("pump", 50, true), Ok(60));
+    }
+
+    #[test]
+    fn validate_duty_kraken_fan_default_range() {
+        let device_support = KrakenX3Support::new();
+        assert_eq!(device_support.validate_duty("fan", 0, false), Ok(0));
+        assert_eq!(device_support.validate_duty("fan", 100, false), Ok(100));
+    }
+
+    #[test]
+    fn validate_duty_smart_device2_fan_range() {
+        let device_support = SmartDevice2Support::new();
+        assert!(device_support.validate_duty("fan1", 10, false).is_err());
+        assert_eq!(device_support.validate_duty("fan1", 10, true), Ok(25));
+        assert_eq!(device_support.validate_duty("fan1", 25, false), Ok(25));
+    }
+
+    #[test]
+    fn add_liquid_temp_parses_max_and_critical() {
+        let device_support = KrakenX3Support::new();
+        let device_id: u8 = 1;
+        let status_map = HashMap::from([
+            ("liquid temperature".to_string(), "33.3".to_string()),
+            ("liquid temperature max".to_string(), "60.0".to_string()),
+            ("liquid temperature crit".to_string(), "70.0".to_string()),
+        ]);
+        let result = device_support.get_temperatures(&status_map, &device_id, None);
+        let liquid = result.iter().find(|temp| temp.name == "liquid").unwrap();
+        assert_eq!(liquid.max, Some(60.0));
+        assert_eq!(liquid.critical, Some(70.0));
+    }
+
+    #[test]
+    fn add_liquid_temp_thresholds_absent_stay_none() {
+        let device_support = KrakenX3Support::new();
+        let device_id: u8 = 1;
+        let status_map = HashMap::from([("liquid temperature".to_string(), "33.3".to_string())]);
+        let result = device_support.get_temperatures(&status_map, &device_id, None);
+        let liquid = result.iter().find(|temp| temp.name == "liquid").unwrap();
+        assert_eq!(liquid.max, None);
+        assert_eq!(liquid.critical, None);
+    }
+
+    #[test]
+    fn temp_status_is_critical() {
+        let below = TempStatus {
+            name: "liquid".to_string(),
+            temp: 50.0,
+            max: Some(60.0),
+            critical: Some(70.0),
+            frontend_name: "Liquid".to_string(),
+            external_name: "LC#1 Liquid".to_string(),
+        };
+        assert!(!below.is_critical());
+
+        let at_critical = TempStatus { temp: 70.0, ..below.clone() };
+        assert!(at_critical.is_critical());
+
+        let no_threshold = TempStatus { temp: 999.0, critical: None, ..below };
+        assert!(!no_threshold.is_critical());
+    }
+
+    #[test]
+    fn extract_status_sets_has_critical_temp() {
+        let device_support = KrakenX3Support::new();
+        let device_id: u8 = 1;
+        let status_map = HashMap::from([
+            ("liquid temperature".to_string(), "75.0".to_string()),
+            ("liquid temperature crit".to_string(), "70.0".to_string()),
+        ]);
+        let status = device_support.extract_status(&status_map, &device_id, None);
+        assert!(status.has_critical_temp);
+    }
 }
\ No newline at end of file
@@ -17,12 +17,16 @@
  ******************************************************************************/
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use log::{debug, error, info, warn};
+use nvml_wrapper::enum_wrappers::device::{Clock, TemperatureSensor};
+use nvml_wrapper::enums::device::UsedGpuMemory;
+use nvml_wrapper::struct_wrappers::device::ProcessInfo;
+use nvml_wrapper::Nvml;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 use tokio::process::Command;
@@ -40,8 +44,18 @@ use crate::setting::Setting;
 const GPU_TEMP_NAME: &str = "GPU Temp";
 const GPU_LOAD_NAME: &str = "GPU Load";
 const GPU_FAN_NAME: &str = "GPU Fan";
+const GPU_POWER_NAME: &str = "GPU Power";
+const GPU_CORE_CLOCK_NAME: &str = "GPU Core Clock";
+const GPU_MEM_CLOCK_NAME: &str = "GPU Mem Clock";
+const GPU_VRAM_NAME: &str = "GPU VRAM";
+const GPU_POWER_CAP_NAME: &str = "GPU Power Cap";
 const DEFAULT_AMD_GPU_NAME: &str = "Radeon Graphics";
 const AMD_HWMON_NAME: &str = "amdgpu";
+const PCI_DEVICES_DIR: &str = "/sys/bus/pci/devices";
+const PCI_VENDOR_NVIDIA: &str = "0x10de";
+const PCI_VENDOR_AMD: &str = "0x1002";
+/// PCI class code prefix for display controllers (VGA/3D/display), per the PCI ID database.
+const PCI_DISPLAY_CONTROLLER_CLASS_PREFIX: &str = "0x03";
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Display, EnumString, Serialize, Deserialize)]
 pub enum GpuType {
@@ -49,25 +63,120 @@ pub enum GpuType {
     AMD,
 }
 
+/// A display-controller PCI device discovered by walking `/sys/bus/pci/devices`, independent of
+/// whether its driver is currently loaded or the card is runtime-suspended. Used to derive a
+/// stable GPU ordering/index even when `nvidia-smi` or the `amdgpu` hwmon chip is temporarily
+/// unavailable.
+#[derive(Debug, Clone)]
+struct PciGpuDevice {
+    /// The PCI slot name (e.g. `0000:01:00.0`), used to sort devices into a stable order.
+    slot: String,
+    vendor_id: String,
+    device_id: String,
+    gpu_type: GpuType,
+}
+
 /// A Repository for GPU devices
 pub struct GpuRepo {
     devices: HashMap<u8, DeviceLock>,
     amd_device_infos: HashMap<u8, HwmonDriverInfo>,
     gpu_type_count: RwLock<HashMap<GpuType, u8>>,
     has_multiple_gpus: RwLock<bool>,
+    /// A persistent NVML handle, initialized once in `new` and reused across every status
+    /// update, so polling doesn't pay an NVML/`nvidia-smi` init cost every tick. `None` when the
+    /// NVML shared library couldn't be loaded (e.g. no Nvidia driver installed), in which case
+    /// `get_nvidia_status` falls back to parsing `nvidia-smi` output.
+    nvml: Option<Nvml>,
+    /// Every display-controller PCI device found at startup, in stable PCI-slot order,
+    /// independent of driver/power state. Drives `starting_nvidia_index` so GPU ordering doesn't
+    /// shift if a card's driver is later unloaded or the card runtime-suspends.
+    pci_gpus: RwLock<Vec<PciGpuDevice>>,
+    /// The `(min, max)` microwatt range of each AMD GPU's `power1_cap`, read once at device
+    /// initialization, so `apply_amd_power_cap` can scale a 0-100% setting into an absolute
+    /// value without re-reading the *_min/*_max sysfs files on every apply.
+    amd_power_caps: RwLock<HashMap<u8, (u32, u32)>>,
 }
 
 impl GpuRepo {
     pub async fn new() -> Result<Self> {
+        let nvml = match Nvml::init() {
+            Ok(nvml) => Some(nvml),
+            Err(err) => {
+                debug!("NVML not available, falling back to nvidia-smi: {}", err);
+                None
+            }
+        };
         Ok(Self {
             devices: HashMap::new(),
             amd_device_infos: HashMap::new(),
             gpu_type_count: RwLock::new(HashMap::new()),
             has_multiple_gpus: RwLock::new(false),
+            nvml,
+            pci_gpus: RwLock::new(Vec::new()),
+            amd_power_caps: RwLock::new(HashMap::new()),
         })
     }
 
+    /// Walks `/sys/bus/pci/devices`, recording every display-controller PCI device (class
+    /// `0x03xxxx`) whose vendor ID is Nvidia's or AMD's, sorted by PCI slot name for a stable
+    /// ordering that survives a driver being unloaded or a card runtime-suspending.
+    async fn discover_pci_gpus() -> Vec<PciGpuDevice> {
+        let mut gpus = vec![];
+        let Ok(mut entries) = tokio::fs::read_dir(PCI_DEVICES_DIR).await else {
+            return gpus;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Ok(class) = tokio::fs::read_to_string(path.join("class")).await else {
+                continue;
+            };
+            if !class.trim().starts_with(PCI_DISPLAY_CONTROLLER_CLASS_PREFIX) {
+                continue;
+            }
+            let Ok(vendor_id) = tokio::fs::read_to_string(path.join("vendor")).await else {
+                continue;
+            };
+            let vendor_id = vendor_id.trim().to_lowercase();
+            let gpu_type = if vendor_id == PCI_VENDOR_NVIDIA {
+                GpuType::Nvidia
+            } else if vendor_id == PCI_VENDOR_AMD {
+                GpuType::AMD
+            } else {
+                continue;
+            };
+            let device_id = tokio::fs::read_to_string(path.join("device")).await
+                .map(|id| id.trim().to_lowercase())
+                .unwrap_or_default();
+            let slot = entry.file_name().to_string_lossy().to_string();
+            gpus.push(PciGpuDevice { slot, vendor_id, device_id, gpu_type });
+        }
+        gpus.sort_by(|a, b| a.slot.cmp(&b.slot));
+        gpus
+    }
+
+    /// The 1-based `type_id` the first Nvidia GPU should get, derived from the PCI scan captured
+    /// at startup (one past the number of non-Nvidia GPUs it found, since AMD GPUs take the ids
+    /// before it), rather than recounting live `nvidia-smi`/hwmon output on every call. Falls
+    /// back to the live AMD count when no PCI scan data was collected (e.g. `/sys/bus/pci`
+    /// unavailable).
+    async fn starting_nvidia_index(&self) -> u8 {
+        let pci_gpus = self.pci_gpus.read().await;
+        if !pci_gpus.is_empty() {
+            let non_nvidia_count = pci_gpus.iter()
+                .filter(|pci_gpu| pci_gpu.gpu_type != GpuType::Nvidia)
+                .count() as u8;
+            return non_nvidia_count + 1;
+        }
+        drop(pci_gpus);
+        if *self.has_multiple_gpus.read().await {
+            self.gpu_type_count.read().await.get(&GpuType::AMD).unwrap_or(&0) + 1
+        } else {
+            1
+        }
+    }
+
     async fn detect_gpu_types(&self) {
+        *self.pci_gpus.write().await = Self::discover_pci_gpus().await;
         {
             let mut type_count = self.gpu_type_count.write().await;
             type_count.insert(GpuType::Nvidia, self.get_nvidia_status().await.len() as u8);
@@ -81,25 +190,51 @@ impl GpuRepo {
         }
     }
 
-    async fn request_statuses(&self) -> Vec<(Status, String)> {
+    /// Polls every known GPU (AMD and Nvidia alike) and tags each status with its already
+    /// assigned, stable `type_id` rather than a freshly recomputed enumeration index, so AMD and
+    /// Nvidia devices in the same system can never have their statuses cross-assigned to each
+    /// other's slot.
+    async fn request_statuses(&self) -> Vec<(u8, Status, String)> {
         let mut statuses = vec![];
+        if !self.amd_device_infos.is_empty() {
+            statuses.extend(self.request_amd_statuses().await);
+        }
         if self.gpu_type_count.read().await.get(&GpuType::Nvidia).unwrap() > &0 {
+            let starting_nvidia_index = self.starting_nvidia_index().await;
             statuses.extend(
-                self.request_nvidia_statuses().await
+                self.request_nvidia_statuses()
+                    .await
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (status, name))| (index as u8 + starting_nvidia_index, status, name))
             )
         }
         statuses
     }
 
+    /// Reads current AMD GPU temp/load/power/clock/VRAM status directly from hwmon, mirroring
+    /// the channels `init_amd_devices`/`initialize_devices` discovered at startup. Unlike Nvidia
+    /// GPUs, AMD GPU status was previously only ever captured once at `initialize_devices` time.
+    async fn request_amd_statuses(&self) -> Vec<(u8, Status, String)> {
+        let mut statuses = vec![];
+        for (&id, amd_device) in self.amd_device_infos.iter() {
+            let mut status_channels = FanFns::extract_fan_statuses(amd_device).await;
+            status_channels.extend(extract_load_status(amd_device).await);
+            let status = Status {
+                channels: status_channels,
+                temps: TempFns::extract_temp_statuses(&id, amd_device).await,
+                ..Default::default()
+            };
+            statuses.push((id, status, amd_device.name.clone()));
+        }
+        statuses
+    }
+
     async fn request_nvidia_statuses(&self) -> Vec<(Status, String)> {
         let has_multiple_gpus: bool = self.has_multiple_gpus.read().await.clone();
         let mut statuses = vec![];
         let nvidia_statuses = self.get_nvidia_status().await;
-        let starting_gpu_index = if has_multiple_gpus {
-            self.gpu_type_count.read().await.get(&GpuType::AMD).unwrap_or(&0) + 1
-        } else {
-            1
-        };
+        let starting_gpu_index = self.starting_nvidia_index().await;
         for (index, nvidia_status) in nvidia_statuses.iter().enumerate() {
             let index = index as u8;
             let mut temps = vec![];
@@ -139,6 +274,48 @@ impl GpuRepo {
                     }
                 )
             }
+            if let Some(power_watts) = nvidia_status.power_watts {
+                channels.push(
+                    ChannelStatus {
+                        name: GPU_POWER_NAME.to_string(),
+                        rpm: None,
+                        duty: Some(power_watts),
+                        pwm_mode: None,
+                    }
+                )
+            }
+            if let Some(core_clock_mhz) = nvidia_status.core_clock_mhz {
+                channels.push(
+                    ChannelStatus {
+                        name: GPU_CORE_CLOCK_NAME.to_string(),
+                        rpm: Some(core_clock_mhz),
+                        duty: None,
+                        pwm_mode: None,
+                    }
+                )
+            }
+            if let Some(mem_clock_mhz) = nvidia_status.mem_clock_mhz {
+                channels.push(
+                    ChannelStatus {
+                        name: GPU_MEM_CLOCK_NAME.to_string(),
+                        rpm: Some(mem_clock_mhz),
+                        duty: None,
+                        pwm_mode: None,
+                    }
+                )
+            }
+            if let (Some(used), Some(total)) = (nvidia_status.vram_used_mb, nvidia_status.vram_total_mb) {
+                if total > 0 {
+                    channels.push(
+                        ChannelStatus {
+                            name: GPU_VRAM_NAME.to_string(),
+                            rpm: None,
+                            duty: Some(used as f64 / total as f64 * 100.0),
+                            pwm_mode: None,
+                        }
+                    )
+                }
+            }
             statuses.push(
                 (
                     Status {
@@ -153,10 +330,68 @@ impl GpuRepo {
         statuses
     }
 
+    /// Reads every Nvidia GPU's status through NVML when available, falling back to parsing
+    /// `nvidia-smi` output only when the NVML shared library couldn't be loaded at startup.
     async fn get_nvidia_status(&self) -> Vec<StatusNvidia> {
+        if let Some(nvml) = &self.nvml {
+            return Self::get_nvidia_status_nvml(nvml);
+        }
+        self.get_nvidia_status_cli().await
+    }
+
+    /// Reads temperature, utilization, and fan speed for every Nvidia GPU directly through NVML,
+    /// avoiding a `nvidia-smi` fork+exec (and its CSV parsing) on every poll.
+    fn get_nvidia_status_nvml(nvml: &Nvml) -> Vec<StatusNvidia> {
+        let device_count = match nvml.device_count() {
+            Ok(count) => count,
+            Err(err) => {
+                error!("Error getting NVML device count: {}", err);
+                return vec![];
+            }
+        };
+        let mut nvidia_statuses = vec![];
+        for index in 0..device_count {
+            let device = match nvml.device_by_index(index) {
+                Ok(device) => device,
+                Err(err) => {
+                    error!("Error getting NVML device {}: {}", index, err);
+                    continue;
+                }
+            };
+            let name = device.name().unwrap_or_else(|_| "Nvidia Graphics".to_string());
+            let temp = device.temperature(TemperatureSensor::Gpu).ok().map(f64::from);
+            let load = device.utilization_rates().ok().map(|rates| rates.gpu as u8);
+            let fan_duty = device.fan_speed(0).ok().map(|speed| speed as u8);
+            let power_watts = device.power_usage().ok().map(|milliwatts| f64::from(milliwatts) / 1000.0);
+            let core_clock_mhz = device.clock_info(Clock::Graphics).ok();
+            let mem_clock_mhz = device.clock_info(Clock::Memory).ok();
+            let (vram_used_mb, vram_total_mb) = device.memory_info().ok()
+                .map(|info| (info.used / 1024 / 1024, info.total / 1024 / 1024))
+                .unzip();
+            nvidia_statuses.push(StatusNvidia {
+                index: index as u8,
+                name,
+                temp,
+                load,
+                fan_duty,
+                power_watts,
+                core_clock_mhz,
+                mem_clock_mhz,
+                vram_used_mb,
+                vram_total_mb,
+            });
+        }
+        nvidia_statuses
+    }
+
+    /// Falls back to shelling out to `nvidia-smi`, used only when NVML itself isn't available.
+    async fn get_nvidia_status_cli(&self) -> Vec<StatusNvidia> {
         let output = Command::new("sh")
             .arg("-c")
-            .arg("nvidia-smi --query-gpu=index,gpu_name,temperature.gpu,utilization.gpu,fan.speed --format=csv,noheader,nounits")
+            .arg(
+                "nvidia-smi --query-gpu=index,gpu_name,temperature.gpu,utilization.gpu,fan.speed,\
+                power.draw,clocks.gr,clocks.mem,memory.used,memory.total --format=csv,noheader,nounits"
+            )
             .output().await;
         match output {
             Ok(out) => {
@@ -181,6 +416,11 @@ impl GpuRepo {
                                 temp: values[2].parse::<f64>().ok(),
                                 load: values[3].parse::<u8>().ok(),
                                 fan_duty: values[4].parse::<u8>().ok(),
+                                power_watts: values.get(5).and_then(|v| v.parse::<f64>().ok()),
+                                core_clock_mhz: values.get(6).and_then(|v| v.parse::<u32>().ok()),
+                                mem_clock_mhz: values.get(7).and_then(|v| v.parse::<u32>().ok()),
+                                vram_used_mb: values.get(8).and_then(|v| v.parse::<u64>().ok()),
+                                vram_total_mb: values.get(9).and_then(|v| v.parse::<u64>().ok()),
                             });
                         }
                     }
@@ -194,6 +434,129 @@ impl GpuRepo {
         }
         vec![]
     }
+
+    /// Drives `nvidia-settings` to put the given GPU's fan(s) into manual control and set them
+    /// all to `duty`, resolving the `gpu:N`/`fan:M` indices `nvidia-settings` expects from the
+    /// device's assigned `type_id`, accounting for the AMD-offset applied in
+    /// `request_nvidia_statuses`/`initialize_devices`.
+    async fn apply_nvidia_fan_duty(&self, device_type_id: u8, duty: u8) -> Result<()> {
+        let starting_nvidia_index = self.starting_nvidia_index().await;
+        let nvidia_index = device_type_id.checked_sub(starting_nvidia_index)
+            .ok_or_else(|| anyhow!("Device #{} is not a recognized Nvidia GPU", device_type_id))?;
+        let fan_start = self.nvidia_fan_offset(nvidia_index);
+        let fan_count = self.nvidia_fan_count(nvidia_index);
+        let mut args = vec![
+            "-a".to_string(),
+            format!("[gpu:{}]/GPUFanControlState=1", nvidia_index),
+        ];
+        for fan_index in fan_start..fan_start + fan_count {
+            args.push("-a".to_string());
+            args.push(format!("[fan:{}]/GPUTargetFanSpeed={}", fan_index, duty));
+        }
+        let output = Command::new("nvidia-settings").args(&args).output().await;
+        match output {
+            Ok(out) if out.status.success() => Ok(()),
+            Ok(out) => {
+                let out_err = String::from_utf8_lossy(&out.stderr).to_string();
+                error!("nvidia-settings failed to apply fan duty: {}", out_err);
+                Err(anyhow!("nvidia-settings failed to apply fan duty: {}", out_err))
+            }
+            Err(err) => {
+                error!("nvidia-settings not available (requires an X display): {}", err);
+                Err(anyhow!("nvidia-settings not available (requires an X display): {}", err))
+            }
+        }
+    }
+
+    /// How many fans NVML reports for this Nvidia GPU, used to drive every one of its fans to
+    /// the same duty. Assumes a single fan when NVML isn't available.
+    fn nvidia_fan_count(&self, nvidia_index: u8) -> u8 {
+        self.nvml.as_ref()
+            .and_then(|nvml| nvml.device_by_index(u32::from(nvidia_index)).ok())
+            .and_then(|device| device.num_fans().ok())
+            .map(|count| count as u8)
+            .unwrap_or(1)
+    }
+
+    /// `nvidia-settings`' `[fan:M]` indices are global across every GPU in the system, not
+    /// per-GPU, so this sums the fan counts of every Nvidia GPU before `nvidia_index`.
+    fn nvidia_fan_offset(&self, nvidia_index: u8) -> u8 {
+        (0..nvidia_index).map(|index| self.nvidia_fan_count(index)).sum()
+    }
+
+    /// Lists every process currently using a GPU's compute or graphics engines - a GPU-scoped
+    /// "top", for diagnosing which process is behind a fan/load spike. Nvidia-only (via NVML);
+    /// returns an empty list when NVML isn't available, since AMD and older Nvidia drivers don't
+    /// expose this.
+    pub async fn get_gpu_processes(&self) -> Vec<GpuProcessStatus> {
+        let Some(nvml) = &self.nvml else {
+            return vec![];
+        };
+        let device_count = match nvml.device_count() {
+            Ok(count) => count,
+            Err(err) => {
+                error!("Error getting NVML device count: {}", err);
+                return vec![];
+            }
+        };
+        let mut processes = vec![];
+        for index in 0..device_count {
+            let Ok(device) = nvml.device_by_index(index) else {
+                continue;
+            };
+            if let Ok(compute_processes) = device.running_compute_processes() {
+                for process in compute_processes {
+                    processes.push(Self::to_gpu_process_status(index as u8, process, GpuProcessKind::Compute).await);
+                }
+            }
+            if let Ok(graphics_processes) = device.running_graphics_processes() {
+                for process in graphics_processes {
+                    processes.push(Self::to_gpu_process_status(index as u8, process, GpuProcessKind::Graphics).await);
+                }
+            }
+        }
+        processes
+    }
+
+    async fn to_gpu_process_status(gpu_index: u8, process: ProcessInfo, kind: GpuProcessKind) -> GpuProcessStatus {
+        GpuProcessStatus {
+            gpu_index,
+            pid: process.pid,
+            name: Self::resolve_process_name(process.pid).await,
+            kind,
+            used_memory_mb: match process.used_gpu_memory {
+                UsedGpuMemory::Used(bytes) => Some(bytes / 1024 / 1024),
+                UsedGpuMemory::Unavailable => None,
+            },
+        }
+    }
+
+    /// Resolves a PID to its command name via `/proc/<pid>/comm`, falling back to the bare PID
+    /// when the process has already exited or `/proc` isn't readable.
+    async fn resolve_process_name(pid: u32) -> String {
+        tokio::fs::read_to_string(format!("/proc/{pid}/comm")).await
+            .map(|name| name.trim().to_string())
+            .unwrap_or_else(|_| format!("pid {pid}"))
+    }
+}
+
+/// The NVML engine a process was observed using.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GpuProcessKind {
+    Compute,
+    Graphics,
+    Unknown,
+}
+
+/// A single process' GPU usage, for a GPU-scoped process list ("GPU top").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuProcessStatus {
+    pub gpu_index: u8,
+    pub pid: u32,
+    pub name: String,
+    pub kind: GpuProcessKind,
+    /// `None` when the driver doesn't report per-process memory usage.
+    pub used_memory_mb: Option<u64>,
 }
 
 #[async_trait]
@@ -206,20 +569,36 @@ impl Repository for GpuRepo {
             let id = index as u8 + 1;
             let mut channels = HashMap::new();
             for channel in amd_device.channels.iter() {
-                if channel.hwmon_type != HwmonChannelType::Fan {
-                    continue;  // only Fan channels currently have controls
+                match channel.hwmon_type {
+                    HwmonChannelType::Fan => {
+                        channels.insert(channel.name.clone(), ChannelInfo {
+                            speed_options: Some(SpeedOptions {
+                                profiles_enabled: false,
+                                fixed_enabled: true,
+                                manual_profiles_enabled: true,
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        });
+                    }
+                    HwmonChannelType::Power if channel.name == GPU_POWER_CAP_NAME => {
+                        if let Some(power_cap_range) = read_amd_power_cap_range(&amd_device.path).await {
+                            self.amd_power_caps.write().await.insert(id, power_cap_range);
+                            channels.insert(channel.name.clone(), ChannelInfo {
+                                speed_options: Some(SpeedOptions {
+                                    profiles_enabled: false,
+                                    fixed_enabled: true,
+                                    manual_profiles_enabled: false,
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                    _ => continue,  // only Fan and Power-cap channels currently have controls
                 }
-                let channel_info = ChannelInfo {
-                    speed_options: Some(SpeedOptions {
-                        profiles_enabled: false,
-                        fixed_enabled: true,
-                        manual_profiles_enabled: true,
-                        ..Default::default()
-                    }),
-                    ..Default::default()
-                };
-                channels.insert(channel.name.clone(), channel_info);
             }
+            self.amd_device_infos.insert(id, amd_device.clone());
             let mut status_channels = FanFns::extract_fan_statuses(amd_device).await;
             status_channels.extend(extract_load_status(amd_device).await);
             let status = Status {
@@ -247,23 +626,32 @@ impl Repository for GpuRepo {
                 Arc::new(RwLock::new(device)),
             );
         }
-        let has_multiple_gpus: bool = self.has_multiple_gpus.read().await.clone();
-        let starting_nvidia_index = if has_multiple_gpus {
-            self.gpu_type_count.read().await.get(&GpuType::AMD).unwrap_or(&0) + 1
-        } else {
-            1
-        };
+        let starting_nvidia_index = self.starting_nvidia_index().await;
         for (index, (status, gpu_name)) in self.request_nvidia_statuses().await.into_iter().enumerate() {
             let id = index as u8 + starting_nvidia_index;
-            // todo: also verify fan is writable...
+            let mut channels = HashMap::new();
+            if status.channels.iter().any(|channel| channel.name == GPU_FAN_NAME) {
+                channels.insert(
+                    GPU_FAN_NAME.to_string(),
+                    ChannelInfo {
+                        speed_options: Some(SpeedOptions {
+                            profiles_enabled: false,
+                            fixed_enabled: true,
+                            manual_profiles_enabled: true,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                );
+            }
             let mut device = Device {
                 name: gpu_name,
                 d_type: DeviceType::GPU,
                 type_id: id,
                 info: Some(DeviceInfo {
+                    channels,
                     temp_max: 100,
                     temp_ext_available: true,
-                    // channels:  // todo: Nvidia fan control channel if applicable
                     ..Default::default()
                 }),
                 ..Default::default()
@@ -293,10 +681,8 @@ impl Repository for GpuRepo {
     async fn update_statuses(&self) -> Result<()> {
         debug!("Updating all GPU device statuses");
         let start_update = Instant::now();
-        // todo: AMD
-        for (index, (status, gpu_name)) in self.request_statuses().await.iter().enumerate() {
-            let index = index as u8 + 1;
-            if let Some(device_lock) = self.devices.get(&index) {
+        for (id, status, gpu_name) in self.request_statuses().await {
+            if let Some(device_lock) = self.devices.get(&id) {
                 device_lock.write().await.set_status(status.clone());
                 debug!("Device: {} status updated: {:?}", gpu_name, status);
             }
@@ -310,14 +696,74 @@ impl Repository for GpuRepo {
 
     async fn shutdown(&self) -> Result<()> {
         debug!("GPU Repository shutdown");
+        for amd_device in self.amd_device_infos.values() {
+            for channel in amd_device.channels.iter().filter(|channel| channel.hwmon_type == HwmonChannelType::Fan) {
+                if let Err(err) = Self::write_amd_pwm_enable(amd_device, channel, channel.pwm_enable_default).await {
+                    error!("Error restoring AMD GPU fan {} to its default pwm_enable value: {}", channel.name, err);
+                }
+            }
+        }
         Ok(())
     }
 
     async fn apply_setting(&self, device_type_id: u8, setting: Setting) -> Result<()> {
-        // todo: change nvidia fan
-        //  nvidia-settings -a "[gpu:0]/GPUFanControlState=1" -a "[fan:0]/GPUTargetFanSpeed=25"
-        // todo: amd? (is hwmon currently, but perhaps we move it in here (check the crates)
-        todo!()
+        if let Some(amd_device) = self.amd_device_infos.get(&device_type_id) {
+            return match setting.channel_name.as_str() {
+                GPU_FAN_NAME => self.apply_amd_fan_setting(amd_device, &setting).await,
+                GPU_POWER_CAP_NAME => self.apply_amd_power_cap(device_type_id, amd_device, &setting).await,
+                _ => Err(anyhow!("Unknown AMD GPU channel: {}", setting.channel_name)),
+            };
+        }
+        if setting.channel_name != GPU_FAN_NAME {
+            return Err(anyhow!("Unknown Nvidia GPU channel: {}", setting.channel_name));
+        }
+        let Some(duty) = setting.speed_fixed else {
+            return Err(anyhow!("Only a fixed speed is currently supported for Nvidia GPU fans"));
+        };
+        self.apply_nvidia_fan_duty(device_type_id, duty).await
+    }
+}
+
+impl GpuRepo {
+    async fn write_amd_pwm_enable(amd_device: &HwmonDriverInfo, channel: &HwmonChannelInfo, value: u8) -> Result<()> {
+        tokio::fs::write(amd_device.path.join(format!("pwm{}_enable", channel.number)), value.to_string())
+            .await
+            .map_err(|err| anyhow!("Error writing pwm{}_enable for {}: {}", channel.number, amd_device.name, err))
+    }
+
+    async fn apply_amd_fan_setting(&self, amd_device: &HwmonDriverInfo, setting: &Setting) -> Result<()> {
+        let Some(channel) = amd_device.channels.iter()
+            .find(|channel| channel.hwmon_type == HwmonChannelType::Fan && channel.name == GPU_FAN_NAME)
+        else {
+            return Err(anyhow!("No AMD GPU fan channel found for {}", amd_device.name));
+        };
+        if setting.reset_to_default == Some(true) {
+            return Self::write_amd_pwm_enable(amd_device, channel, channel.pwm_enable_default).await;
+        }
+        let Some(duty) = setting.speed_fixed else {
+            return Err(anyhow!("Only a fixed speed is currently supported for AMD GPU fans"));
+        };
+        Self::write_amd_pwm_enable(amd_device, channel, 1).await?;
+        let pwm_value = (duty.min(100) as u32 * 255 / 100) as u8;
+        tokio::fs::write(amd_device.path.join(format!("pwm{}", channel.number)), pwm_value.to_string())
+            .await
+            .map_err(|err| anyhow!("Error writing pwm{} for {}: {}", channel.number, amd_device.name, err))
+    }
+
+    async fn apply_amd_power_cap(&self, device_type_id: u8, amd_device: &HwmonDriverInfo, setting: &Setting) -> Result<()> {
+        let Some((min_uw, max_uw)) = self.amd_power_caps.read().await.get(&device_type_id).copied() else {
+            return Err(anyhow!("No power cap range known for {}", amd_device.name));
+        };
+        let Some(percent) = setting.speed_fixed else {
+            return Err(anyhow!("A percentage of the supported power cap range is required to set the AMD GPU power cap"));
+        };
+        let target_uw = min_uw as f64 + (max_uw - min_uw) as f64 * percent.min(100) as f64 / 100.0;
+        tokio::fs::write(
+            amd_device.path.join("device").join("power1_cap"),
+            (target_uw.round() as u64).to_string(),
+        )
+            .await
+            .map_err(|err| anyhow!("Error writing power1_cap for {}: {}", amd_device.name, err))
     }
 }
 
@@ -356,6 +802,18 @@ async fn init_amd_devices() -> Vec<HwmonDriverInfo> {
         if let Some(load_channel) = init_amd_load(&path, &device_name).await {
             channels.push(load_channel)
         }
+        if let Some(power_channel) = init_amd_power(&path).await {
+            channels.push(power_channel)
+        }
+        if let Some(power_cap_channel) = init_amd_power_cap(&path).await {
+            channels.push(power_cap_channel)
+        }
+        if let Some(clock_channel) = init_amd_clock(&path).await {
+            channels.push(clock_channel)
+        }
+        if let Some(vram_channel) = init_amd_vram(&path).await {
+            channels.push(vram_channel)
+        }
         let model = DeviceFns::get_device_model_name(&path).await;
         let hwmon_driver_info = HwmonDriverInfo {
             name: device_name,
@@ -390,10 +848,119 @@ async fn init_amd_load(base_path: &PathBuf, device_name: &String) -> Option<Hwmo
     }
 }
 
+/// Reads a single numeric sysfs node and divides it by `divisor` (e.g. microwatts to watts,
+/// Hz to MHz).
+async fn read_amd_scaled_value(path: &Path, divisor: f64) -> Option<f64> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    Some(contents.trim().parse::<f64>().ok()? / divisor)
+}
+
+async fn init_amd_power(base_path: &Path) -> Option<HwmonChannelInfo> {
+    if tokio::fs::read_to_string(base_path.join("device").join("power1_average")).await.is_err() {
+        warn!("No AMDGPU power reading found: {:?}/device/power1_average", base_path);
+        return None;
+    }
+    Some(HwmonChannelInfo {
+        hwmon_type: HwmonChannelType::Power,
+        name: GPU_POWER_NAME.to_string(),
+        ..Default::default()
+    })
+}
+
+async fn init_amd_power_cap(base_path: &Path) -> Option<HwmonChannelInfo> {
+    if tokio::fs::read_to_string(base_path.join("device").join("power1_cap")).await.is_err() {
+        warn!("No AMDGPU power cap control found: {:?}/device/power1_cap", base_path);
+        return None;
+    }
+    Some(HwmonChannelInfo {
+        hwmon_type: HwmonChannelType::Power,
+        name: GPU_POWER_CAP_NAME.to_string(),
+        ..Default::default()
+    })
+}
+
+/// Reads the `(min, max)` microwatt range AMD's `power1_cap` accepts, used to scale a 0-100%
+/// `Setting::speed_fixed` into an absolute wattage to write back.
+async fn read_amd_power_cap_range(base_path: &Path) -> Option<(u32, u32)> {
+    let min = tokio::fs::read_to_string(base_path.join("device").join("power1_cap_min")).await
+        .ok()?.trim().parse::<u32>().ok()?;
+    let max = tokio::fs::read_to_string(base_path.join("device").join("power1_cap_max")).await
+        .ok()?.trim().parse::<u32>().ok()?;
+    Some((min, max))
+}
+
+async fn init_amd_clock(base_path: &Path) -> Option<HwmonChannelInfo> {
+    if tokio::fs::read_to_string(base_path.join("freq1_input")).await.is_err() {
+        warn!("No AMDGPU core clock found: {:?}/freq1_input", base_path);
+        return None;
+    }
+    Some(HwmonChannelInfo {
+        hwmon_type: HwmonChannelType::Freq,
+        name: GPU_CORE_CLOCK_NAME.to_string(),
+        ..Default::default()
+    })
+}
+
+async fn init_amd_vram(base_path: &Path) -> Option<HwmonChannelInfo> {
+    let vram_used = base_path.join("device").join("mem_info_vram_used");
+    let vram_total = base_path.join("device").join("mem_info_vram_total");
+    if tokio::fs::read_to_string(&vram_used).await.is_err()
+        || tokio::fs::read_to_string(&vram_total).await.is_err() {
+        warn!("No AMDGPU VRAM usage found: {:?}/device/mem_info_vram_used", base_path);
+        return None;
+    }
+    Some(HwmonChannelInfo {
+        hwmon_type: HwmonChannelType::Load,
+        name: GPU_VRAM_NAME.to_string(),
+        ..Default::default()
+    })
+}
+
 async fn extract_load_status(driver: &HwmonDriverInfo) -> Vec<ChannelStatus> {
     let mut channels = vec![];
     for channel in driver.channels.iter() {
-        if channel.hwmon_type != HwmonChannelType::Load {
+        if channel.hwmon_type == HwmonChannelType::Power && channel.name == GPU_POWER_NAME {
+            if let Some(watts) = read_amd_scaled_value(
+                &driver.path.join("device").join("power1_average"), 1_000_000.0,
+            ).await {
+                channels.push(ChannelStatus {
+                    name: channel.name.clone(),
+                    rpm: None,
+                    duty: Some(watts),
+                    pwm_mode: None,
+                });
+            }
+            continue;
+        }
+        if channel.hwmon_type == HwmonChannelType::Freq && channel.name == GPU_CORE_CLOCK_NAME {
+            if let Some(mhz) = read_amd_scaled_value(&driver.path.join("freq1_input"), 1_000_000.0).await {
+                channels.push(ChannelStatus {
+                    name: channel.name.clone(),
+                    rpm: Some(mhz.round() as u32),
+                    duty: None,
+                    pwm_mode: None,
+                });
+            }
+            continue;
+        }
+        if channel.hwmon_type == HwmonChannelType::Load && channel.name == GPU_VRAM_NAME {
+            let used = tokio::fs::read_to_string(driver.path.join("device").join("mem_info_vram_used")).await
+                .ok().and_then(|s| s.trim().parse::<u64>().ok());
+            let total = tokio::fs::read_to_string(driver.path.join("device").join("mem_info_vram_total")).await
+                .ok().and_then(|s| s.trim().parse::<u64>().ok());
+            if let (Some(used), Some(total)) = (used, total) {
+                if total > 0 {
+                    channels.push(ChannelStatus {
+                        name: channel.name.clone(),
+                        rpm: None,
+                        duty: Some(used as f64 / total as f64 * 100.0),
+                        pwm_mode: None,
+                    });
+                }
+            }
+            continue;
+        }
+        if channel.hwmon_type != HwmonChannelType::Load || channel.name != GPU_LOAD_NAME {
             continue;
         }
         let load = tokio::fs::read_to_string(
@@ -418,4 +985,9 @@ struct StatusNvidia {
     temp: Option<f64>,
     load: Option<u8>,
     fan_duty: Option<u8>,
+    power_watts: Option<f64>,
+    core_clock_mhz: Option<u32>,
+    mem_clock_mhz: Option<u32>,
+    vram_used_mb: Option<u64>,
+    vram_total_mb: Option<u64>,
 }
\ No newline at end of file
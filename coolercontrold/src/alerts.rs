@@ -23,21 +23,26 @@ use crate::device::UID;
 use crate::setting::{ChannelMetric, ChannelSource};
 use crate::{cc_fs, AllDevices};
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::{DateTime, Local};
 use const_format::concatcp;
 use hashlink::LinkedHashMap;
+use hotwatch::{Event, EventKind, Hotwatch};
 use lazy_format::lazy_format;
 use log::{error, info};
 use moro_local::Scope;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Display;
 use std::ops::Not;
 use std::path::Path;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 use strum::{Display, EnumString};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
 const DEFAULT_ALERT_CONFIG_FILE_PATH: &str = concatcp!(DEFAULT_CONFIG_DIR, "/alerts.json");
@@ -51,17 +56,61 @@ pub struct Alert {
     pub uid: UID,
     pub name: AlertName,
     pub channel_source: ChannelSource,
-    pub min: f64,
-    pub max: f64,
+
+    /// The critical-tier lower bound: crossing below this (or above `crit_max`) puts the Alert
+    /// into `AlertState::Critical`. Named `min` in Alerts persisted before severity tiers
+    /// existed; the `serde` alias keeps those still deserializing correctly.
+    #[serde(alias = "min")]
+    pub crit_min: f64,
+
+    /// The critical-tier upper bound. See `crit_min`.
+    #[serde(alias = "max")]
+    pub crit_max: f64,
+
+    /// The warning-tier lower bound, inside `crit_min`. `None` (the default) means this Alert
+    /// has no warning tier and only ever distinguishes `Inactive`/`Critical`, matching its
+    /// original two-state behavior.
+    #[serde(default)]
+    pub warn_min: Option<f64>,
+
+    /// The warning-tier upper bound, inside `crit_max`. See `warn_min`.
+    #[serde(default)]
+    pub warn_max: Option<f64>,
+
+    /// How long the channel value must stay past a threshold before the Alert actually fires,
+    /// so a single noisy reading doesn't trip it. `None` (the default) fires immediately, matching
+    /// the original behavior.
+    #[serde(default)]
+    pub trigger_delay_secs: Option<u64>,
+
+    /// How long the channel value must stay back within a less severe tier before the Alert
+    /// follows it there, so a value hovering right at a threshold doesn't flap. `None` (the
+    /// default) follows immediately, matching the original behavior.
+    #[serde(default)]
+    pub reset_delay_secs: Option<u64>,
+
     pub state: AlertState,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Display, EnumString, Serialize, Deserialize, JsonSchema)]
 pub enum AlertState {
-    Active,
+    Critical,
+    Warning,
     Inactive,
 }
 
+impl AlertState {
+    /// Higher is more severe. Used to tell whether a pending transition is an escalation
+    /// (governed by `trigger_delay_secs`) or a de-escalation (governed by `reset_delay_secs`).
+    const fn severity(&self) -> u8 {
+        match self {
+            Self::Inactive => 0,
+            Self::Warning => 1,
+            Self::Critical => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AlertLog {
     pub uid: UID,
@@ -76,18 +125,149 @@ impl Default for AlertLog {
         AlertLog {
             uid: "Unknown".to_string(),
             name: "Unknown".to_string(),
-            state: AlertState::Active,
+            state: AlertState::Critical,
             message: "Unknown".to_string(),
             timestamp: Local::now(),
         }
     }
 }
 
+/// The minimum time between two notifications through the same sink for the same Alert uid, so a
+/// flapping sensor right at a threshold can't spawn hundreds of commands/webhooks/notifications.
+const SINK_RATE_LIMIT: Duration = Duration::from_secs(30);
+
+/// A destination for Alert state-change notifications, beyond the in-process broadcast handled by
+/// `AlertHandle`. Implemented by `CommandSink`, `WebhookSink`, and `DesktopSink`; new sink kinds
+/// can be added without touching the dispatch loop in
+/// `AlertController::dispatch_to_notification_sinks`.
+#[async_trait(?Send)]
+trait NotificationSink {
+    async fn notify(&self, log: &AlertLog) -> Result<()>;
+}
+
+/// Persisted configuration for a `NotificationSink`, either applying to every Alert (a global
+/// sink) or stored alongside a specific one. Kept separate from the trait object so sinks can be
+/// serialized in `AlertConfigFile` and rebuilt fresh on load.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum NotificationSinkConfig {
+    /// Runs `command` via the shell, with the `AlertLog` fields exported as `ALERT_*` env vars
+    /// and the full `AlertLog` JSON piped to stdin.
+    Command { command: String },
+    /// POSTs the `AlertLog` JSON to `url`.
+    Webhook { url: String },
+    /// Shows a desktop notification via the system notification service.
+    Desktop,
+}
+
+impl NotificationSinkConfig {
+    fn build(&self) -> Box<dyn NotificationSink> {
+        match self {
+            Self::Command { command } => Box::new(CommandSink {
+                command: command.clone(),
+            }),
+            Self::Webhook { url } => Box::new(WebhookSink { url: url.clone() }),
+            Self::Desktop => Box::new(DesktopSink),
+        }
+    }
+}
+
+/// Runs a user-specified shell command for every notification, with the Alert fields available
+/// both as env vars and as JSON on stdin, so simple scripts and JSON-aware tools both work.
+struct CommandSink {
+    command: String,
+}
+
+#[async_trait(?Send)]
+impl NotificationSink for CommandSink {
+    async fn notify(&self, log: &AlertLog) -> Result<()> {
+        let log_json = serde_json::to_string(log)?;
+        let mut child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .env("ALERT_UID", &log.uid)
+            .env("ALERT_NAME", &log.name)
+            .env("ALERT_STATE", log.state.to_string())
+            .env("ALERT_MESSAGE", &log.message)
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Spawning notification command: {}", self.command))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(log_json.as_bytes()).await;
+        }
+        child
+            .wait()
+            .await
+            .with_context(|| format!("Running notification command: {}", self.command))?;
+        Ok(())
+    }
+}
+
+/// POSTs the `AlertLog` as JSON to a webhook URL.
+struct WebhookSink {
+    url: String,
+}
+
+#[async_trait(?Send)]
+impl NotificationSink for WebhookSink {
+    async fn notify(&self, log: &AlertLog) -> Result<()> {
+        reqwest::Client::new()
+            .post(&self.url)
+            .json(log)
+            .send()
+            .await
+            .with_context(|| format!("Sending Alert webhook to {}", self.url))?;
+        Ok(())
+    }
+}
+
+/// Shows a desktop notification via the system notification service.
+struct DesktopSink;
+
+#[async_trait(?Send)]
+impl NotificationSink for DesktopSink {
+    async fn notify(&self, log: &AlertLog) -> Result<()> {
+        notify_rust::Notification::new()
+            .summary(&format!("{} Alert: {}", log.state, log.name))
+            .body(&log.message)
+            .show()
+            .with_context(|| "Showing desktop notification")?;
+        Ok(())
+    }
+}
+
 pub struct AlertController {
     all_devices: AllDevices,
     alerts: RefCell<LinkedHashMap<UID, Alert>>,
     alert_handle: RefCell<Option<AlertHandle>>,
     logs: RefCell<VecDeque<AlertLog>>,
+    /// Set right before `save_alert_data_to_config` writes `alerts.json`, so the file watcher
+    /// spawned by `watch_for_config_changes` can tell its own write apart from an external edit
+    /// and skip reloading what it just saved.
+    expecting_self_write: RefCell<bool>,
+    /// The configured notification sinks, rebuilt from `notification_sink_configs` on load and
+    /// whenever `set_notification_sinks` is called.
+    notification_sinks: RefCell<Vec<Box<dyn NotificationSink>>>,
+    notification_sink_configs: RefCell<Vec<NotificationSinkConfig>>,
+    /// The last time each (Alert uid, sink index) pair actually fired, so a flapping Alert can't
+    /// spam a sink more often than `SINK_RATE_LIMIT`. Not persisted: a restart starts every sink
+    /// fresh for every Alert.
+    sink_last_fired: RefCell<HashMap<(UID, usize), Instant>>,
+    /// For an Alert currently mid-transition to a new tier, the tier it's heading to and when
+    /// that first became true. Cleared once the delay elapses and the transition actually fires,
+    /// or as soon as the channel value returns to the Alert's current tier. Not persisted: a
+    /// restart re-measures from scratch.
+    pending_transitions: RefCell<HashMap<UID, (AlertState, Instant)>>,
+    /// Alert uids currently acknowledged/snoozed, and when that snooze expires. While a uid is
+    /// present and unexpired, `transition_alert_state` still tracks the Alert's state internally
+    /// but suppresses pushing it to `alerts_to_fire`, so it stops broadcasting without being
+    /// deleted. Persisted so a restart doesn't immediately re-spam an Alert the user just
+    /// acknowledged.
+    snoozed_until: RefCell<HashMap<UID, DateTime<Local>>>,
+    /// Set by every mutating call (`create`, `update`, `delete`, `acknowledge`,
+    /// `set_notification_sinks`) and cleared once `watch_for_shutdown`'s periodic flush has
+    /// actually persisted it, so that flush does nothing on ticks where nothing changed.
+    dirty: RefCell<bool>,
 }
 
 impl AlertController {
@@ -98,12 +278,40 @@ impl AlertController {
             alerts: RefCell::new(LinkedHashMap::new()),
             alert_handle: RefCell::new(None),
             logs: RefCell::new(VecDeque::with_capacity(LOG_BUFFER_SIZE)),
+            expecting_self_write: RefCell::new(false),
+            notification_sinks: RefCell::new(Vec::new()),
+            notification_sink_configs: RefCell::new(Vec::new()),
+            sink_last_fired: RefCell::new(HashMap::new()),
+            pending_transitions: RefCell::new(HashMap::new()),
+            snoozed_until: RefCell::new(HashMap::new()),
+            dirty: RefCell::new(false),
         };
         alert_controller.load_data_from_alert_config_file().await?;
         Ok(alert_controller)
     }
 
-    /// Watches for shutdown and saves the current Alert data to the Alert configuration file.
+    /// Replaces the configured notification sinks and persists them.
+    pub async fn set_notification_sinks(&self, configs: Vec<NotificationSinkConfig>) -> Result<()> {
+        let sinks = configs.iter().map(NotificationSinkConfig::build).collect();
+        self.notification_sinks.replace(sinks);
+        self.notification_sink_configs.replace(configs);
+        Ok(self.mark_dirty())
+    }
+
+    /// Marks the in-memory Alert data as needing a flush to disk, picked up by the periodic
+    /// persistence task spawned in `watch_for_shutdown` rather than written synchronously here.
+    fn mark_dirty(&self) {
+        self.dirty.replace(true);
+    }
+
+    /// How often the background persistence task checks the `dirty` flag and, if set, flushes
+    /// the current Alert data (including `logs`) to disk. Keeps `create`/`update`/`delete`/
+    /// `acknowledge` from each paying for a full synchronous rewrite of `alerts.json`.
+    const PERSIST_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Spawns the background task that periodically flushes Alert data to the Alert
+    /// configuration file while `dirty`, and guarantees one last flush on shutdown regardless of
+    /// `dirty`, so a crash between flushes is the only way to lose unsaved state.
     pub fn watch_for_shutdown<'s>(
         controller: &Rc<AlertController>,
         cancellation_token: CancellationToken,
@@ -111,12 +319,108 @@ impl AlertController {
     ) {
         let alert_controller = controller.clone();
         main_scope.spawn(async move {
-            cancellation_token.cancelled().await;
+            loop {
+                tokio::select! {
+                    () = tokio::time::sleep(Self::PERSIST_INTERVAL) => {
+                        if alert_controller.dirty.replace(false) {
+                            if let Err(err) = alert_controller.save_alert_data_to_config().await {
+                                error!("Error persisting Alert data: {err}");
+                                alert_controller.dirty.replace(true);
+                            }
+                        }
+                    }
+                    () = cancellation_token.cancelled() => break,
+                }
+            }
             info!("Shutting down Alert Controller");
             let _ = alert_controller.save_alert_data_to_config().await;
         });
     }
 
+    /// Watches `alerts.json` for external changes (e.g. hand edits or a sync from a dotfiles
+    /// repo) and hot-reloads it, so the daemon doesn't require a restart to pick them up. Modify
+    /// events are debounced: a burst of events within `DEBOUNCE_PERIOD` of each other settles down
+    /// to a single reload. Spawned alongside `watch_for_shutdown`.
+    pub fn watch_for_config_changes<'s>(
+        controller: &Rc<AlertController>,
+        cancellation_token: CancellationToken,
+        main_scope: &'s Scope<'s, 's, Result<()>>,
+    ) -> Result<()> {
+        const DEBOUNCE_PERIOD: Duration = Duration::from_millis(500);
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel::<()>();
+        let path = Path::new(DEFAULT_ALERT_CONFIG_FILE_PATH).to_path_buf();
+        let mut hotwatch =
+            Hotwatch::new().context("Initializing Alert configuration file watcher")?;
+        hotwatch
+            .watch(&path, move |event: Event| {
+                if matches!(event.kind, EventKind::Modify(_)) {
+                    let _ = event_tx.send(());
+                }
+            })
+            .with_context(|| format!("Watching Alert configuration file: {path:?}"))?;
+        let alert_controller = Rc::clone(controller);
+        main_scope.spawn(async move {
+            // Keep the watcher (and its background thread) alive for as long as this task runs.
+            let _hotwatch = hotwatch;
+            loop {
+                tokio::select! {
+                    () = cancellation_token.cancelled() => break,
+                    received = event_rx.recv() => {
+                        if received.is_none() {
+                            break;
+                        }
+                        // Coalesce a burst of events: keep resetting the debounce timer for as
+                        // long as more events keep arriving within DEBOUNCE_PERIOD.
+                        loop {
+                            tokio::select! {
+                                () = tokio::time::sleep(DEBOUNCE_PERIOD) => break,
+                                next = event_rx.recv() => {
+                                    if next.is_none() {
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        if alert_controller.expecting_self_write.replace(false) {
+                            continue; // this was our own save_alert_data_to_config write
+                        }
+                        if let Err(err) = alert_controller.reload_from_external_change().await {
+                            error!("Failed to reload Alert configuration after external change: {err}");
+                        }
+                    }
+                }
+            }
+            Ok(())
+        });
+        Ok(())
+    }
+
+    /// Re-reads `alerts.json` after an external change and merges it in, preserving the live
+    /// `AlertState` of any Alert whose `uid` already exists - mirroring the merge `update` already
+    /// does - so a config reload can never spuriously re-fire an Alert that's already active.
+    async fn reload_from_external_change(&self) -> Result<()> {
+        info!("Reloading Alert configuration after external change");
+        let path = Path::new(DEFAULT_ALERT_CONFIG_FILE_PATH).to_path_buf();
+        let config_contents = cc_fs::read_txt(&path)
+            .await
+            .with_context(|| format!("Reading Alert configuration file {path:?}"))?;
+        let alert_config: AlertConfigFile = serde_json::from_str(&config_contents)
+            .with_context(|| format!("Parsing Alert configuration file {path:?}"))?;
+        let mut alerts_lock = self.alerts.borrow_mut();
+        let previous_states: HashMap<UID, AlertState> = alerts_lock
+            .iter()
+            .map(|(uid, alert)| (uid.clone(), alert.state.clone()))
+            .collect();
+        alerts_lock.clear();
+        for mut alert in alert_config.alerts {
+            if let Some(state) = previous_states.get(&alert.uid) {
+                alert.state = state.clone();
+            }
+            alerts_lock.insert(alert.uid.clone(), alert);
+        }
+        Ok(())
+    }
+
     /// Sets the `AlertHandle` for the `AlertController`.
     ///
     /// The `AlertHandle` is used to broadcast notifications when an `Alert` state changes.
@@ -142,6 +446,8 @@ impl AlertController {
             let default_alert_config = serde_json::to_string(&AlertConfigFile {
                 alerts: Vec::with_capacity(0),
                 logs: Vec::with_capacity(0),
+                notification_sinks: Vec::with_capacity(0),
+                acknowledgements: HashMap::new(),
             })?;
             cc_fs::write_string(&path, default_alert_config)
                 .await
@@ -165,20 +471,47 @@ impl AlertController {
             logs_lock.clear();
             logs_lock.extend(alert_config.logs);
         }
+        {
+            let sinks = alert_config
+                .notification_sinks
+                .iter()
+                .map(NotificationSinkConfig::build)
+                .collect();
+            self.notification_sinks.replace(sinks);
+            self.notification_sink_configs
+                .replace(alert_config.notification_sinks);
+        }
+        self.snoozed_until
+            .replace(alert_config.acknowledgements);
         Ok(())
     }
 
     /// Saves the current Alert data to the Alert configuration file.
     async fn save_alert_data_to_config(&self) -> Result<()> {
+        self.expecting_self_write.replace(true);
         let alert_config = AlertConfigFile {
             alerts: self.alerts.borrow().values().cloned().collect(),
             logs: self.logs.borrow().iter().cloned().collect(),
+            notification_sinks: self.notification_sink_configs.borrow().clone(),
+            acknowledgements: self.snoozed_until.borrow().clone(),
         };
         let alert_config_json = serde_json::to_string(&alert_config)?;
-        cc_fs::write_string(DEFAULT_ALERT_CONFIG_FILE_PATH, alert_config_json)
+        let path = Path::new(DEFAULT_ALERT_CONFIG_FILE_PATH);
+        let tmp_path = path.with_extension("json.tmp");
+        let mut tmp_file = tokio::fs::File::create(&tmp_path)
             .await
-            .with_context(|| "Writing Alert Configuration File")?;
-        Ok(())
+            .with_context(|| format!("Creating temp Alert configuration file: {tmp_path:?}"))?;
+        tmp_file
+            .write_all(alert_config_json.as_bytes())
+            .await
+            .with_context(|| format!("Writing temp Alert configuration file: {tmp_path:?}"))?;
+        tmp_file
+            .sync_all()
+            .await
+            .with_context(|| format!("Fsyncing temp Alert configuration file: {tmp_path:?}"))?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .with_context(|| format!("Renaming temp Alert configuration file over: {path:?}"))
     }
 
     /// Returns a tuple of all available Alerts and logs: (alerts, logs)
@@ -197,7 +530,7 @@ impl AlertController {
             .into());
         }
         self.alerts.borrow_mut().insert(alert.uid.clone(), alert);
-        self.save_alert_data_to_config().await
+        Ok(self.mark_dirty())
     }
 
     /// Updates an existing Alert
@@ -215,7 +548,7 @@ impl AlertController {
             alert.state = current_state;
             alerts_lock.insert(alert.uid.clone(), alert);
         }
-        self.save_alert_data_to_config().await
+        Ok(self.mark_dirty())
     }
 
     /// Deletes an existing Alert
@@ -227,24 +560,92 @@ impl AlertController {
             .into());
         }
         self.alerts.borrow_mut().remove(&alert_uid);
-        self.save_alert_data_to_config().await
+        Ok(self.mark_dirty())
+    }
+
+    /// Acknowledges/snoozes an Alert for `duration`, so it stops broadcasting without being
+    /// deleted. Its state is still tracked internally; once the snooze expires, it automatically
+    /// re-arms and logs a "still active" entry if the condition hasn't cleared on its own.
+    pub async fn acknowledge(&self, uid: &UID, duration: Duration) -> Result<()> {
+        if self.alerts.borrow().contains_key(uid).not() {
+            return Err(CCError::NotFound {
+                msg: format!("Alert with uid {uid} does not exist"),
+            }
+            .into());
+        }
+        let until = Local::now()
+            + chrono::Duration::from_std(duration)
+                .with_context(|| format!("Invalid snooze duration: {duration:?}"))?;
+        self.snoozed_until.borrow_mut().insert(uid.clone(), until);
+        Ok(self.mark_dirty())
     }
 
     /// Processes all Alerts, firing off messages if an alert state has changed.
     /// This function should be called in the main loop
-    pub fn process_alerts(&self) {
+    pub async fn process_alerts(&self) {
         let alerts_to_fire = self.process_and_collect_alerts_to_fire();
-        for alert_data in alerts_to_fire {
-            let log = self.log_alert_state_change(
-                alert_data.0.uid,
-                alert_data.0.name,
-                alert_data.0.state,
-                alert_data.1,
-            );
+        if alerts_to_fire.is_empty() {
+            return;
+        }
+        let logs: Vec<AlertLog> = alerts_to_fire
+            .into_iter()
+            .map(|alert_data| {
+                self.log_alert_state_change(
+                    alert_data.0.uid,
+                    alert_data.0.name,
+                    alert_data.0.state,
+                    alert_data.1,
+                )
+            })
+            .collect();
+        for log in &logs {
             if let Some(handle) = self.alert_handle.borrow().as_ref() {
-                handle.broadcast_alert_state_change(log);
+                handle.broadcast_alert_state_change(log.clone());
             }
         }
+        self.dispatch_to_notification_sinks(logs).await;
+    }
+
+    /// Fans the given logs out to every configured notification sink, independently and
+    /// without blocking on one another. Each `(alert, sink)` pair is rate-limited so a
+    /// flapping alert can't spam an external command/webhook/desktop notification.
+    async fn dispatch_to_notification_sinks(&self, logs: Vec<AlertLog>) {
+        if self.notification_sinks.borrow().is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let mut due_logs = Vec::new();
+        {
+            let mut last_fired = self.sink_last_fired.borrow_mut();
+            for log in logs {
+                for sink_index in 0..self.notification_sinks.borrow().len() {
+                    let key = (log.uid.clone(), sink_index);
+                    let is_due = last_fired
+                        .get(&key)
+                        .map_or(true, |last| now.duration_since(*last) >= SINK_RATE_LIMIT);
+                    if is_due {
+                        last_fired.insert(key, now);
+                        due_logs.push((log.clone(), sink_index));
+                    }
+                }
+            }
+        }
+        if due_logs.is_empty() {
+            return;
+        }
+        let sinks = &self.notification_sinks;
+        moro_local::async_scope!(|scope| {
+            for (log, sink_index) in due_logs {
+                let sinks = sinks;
+                scope.spawn(async move {
+                    let result = sinks.borrow()[sink_index].notify(&log).await;
+                    if let Err(err) = result {
+                        error!("Error notifying Alert sink for Alert {}: {err}", log.uid);
+                    }
+                });
+            }
+        })
+        .await;
     }
 
     /// Collects all Alerts that need firing
@@ -253,7 +654,12 @@ impl AlertController {
         let mut alerts_to_fire = Vec::new();
         for alert in self.alerts.borrow_mut().values_mut() {
             let Some(device) = self.all_devices.get(&alert.channel_source.device_uid) else {
-                Self::activate_alert(&mut alerts_to_fire, alert, "Device not found");
+                self.transition_alert_state(
+                    &mut alerts_to_fire,
+                    alert,
+                    AlertState::Critical,
+                    "Device not found",
+                );
                 continue;
             };
             let most_recent_status = device.borrow().status_current().unwrap();
@@ -263,7 +669,12 @@ impl AlertController {
                     .iter()
                     .find(|temp| temp.name == alert.channel_source.channel_name)
                 else {
-                    Self::activate_alert(&mut alerts_to_fire, alert, "Device Channel not found");
+                    self.transition_alert_state(
+                        &mut alerts_to_fire,
+                        alert,
+                        AlertState::Critical,
+                        "Device Channel not found",
+                    );
                     continue;
                 };
                 temp_status.temp
@@ -273,15 +684,21 @@ impl AlertController {
                     .iter()
                     .find(|channel| channel.name == alert.channel_source.channel_name)
                 else {
-                    Self::activate_alert(&mut alerts_to_fire, alert, "Device Channel not found");
+                    self.transition_alert_state(
+                        &mut alerts_to_fire,
+                        alert,
+                        AlertState::Critical,
+                        "Device Channel not found",
+                    );
                     continue;
                 };
                 match alert.channel_source.channel_metric {
                     ChannelMetric::Duty => {
                         let Some(duty) = channel_status.duty else {
-                            Self::activate_alert(
+                            self.transition_alert_state(
                                 &mut alerts_to_fire,
                                 alert,
+                                AlertState::Critical,
                                 "Device Channel Duty Metric not found",
                             );
                             continue;
@@ -290,9 +707,10 @@ impl AlertController {
                     }
                     ChannelMetric::Load => {
                         let Some(load) = channel_status.duty else {
-                            Self::activate_alert(
+                            self.transition_alert_state(
                                 &mut alerts_to_fire,
                                 alert,
+                                AlertState::Critical,
                                 "Device Channel Load Metric not found",
                             );
                             continue;
@@ -301,9 +719,10 @@ impl AlertController {
                     }
                     ChannelMetric::RPM => {
                         let Some(rpm) = channel_status.rpm else {
-                            Self::activate_alert(
+                            self.transition_alert_state(
                                 &mut alerts_to_fire,
                                 alert,
+                                AlertState::Critical,
                                 "Device Channel RPM Metric not found",
                             );
                             continue;
@@ -312,9 +731,10 @@ impl AlertController {
                     }
                     ChannelMetric::Freq => {
                         let Some(freq) = channel_status.freq else {
-                            Self::activate_alert(
+                            self.transition_alert_state(
                                 &mut alerts_to_fire,
                                 alert,
+                                AlertState::Critical,
                                 "Device Channel Freq Metric not found",
                             );
                             continue;
@@ -327,69 +747,148 @@ impl AlertController {
                     }
                 }
             };
-            if channel_value > alert.max {
-                if alert.state == AlertState::Active {
-                    continue;
-                }
-                let channel_name = alert.channel_source.channel_name.clone();
-                let max = alert.max;
-                Self::activate_alert(
-                    &mut alerts_to_fire,
-                    alert,
+            let channel_name = alert.channel_source.channel_name.clone();
+            let is_critical = channel_value > alert.crit_max || channel_value < alert.crit_min;
+            let is_warning = alert
+                .warn_max
+                .map_or(false, |warn_max| channel_value > warn_max)
+                || alert
+                    .warn_min
+                    .map_or(false, |warn_min| channel_value < warn_min);
+            let new_state = if is_critical {
+                AlertState::Critical
+            } else if is_warning {
+                AlertState::Warning
+            } else {
+                AlertState::Inactive
+            };
+            if new_state == alert.state {
+                // Cancel any pending transition into a different tier: the reading is back on
+                // the Alert's current tier, so a stale pending entry must not outlive this tick
+                // and fire on some later, unrelated excursion into that same tier.
+                self.pending_transitions.borrow_mut().remove(&alert.uid);
+                self.rearm_if_snooze_expired(alert, &mut alerts_to_fire);
+                continue;
+            }
+            let message = if is_critical {
+                if channel_value > alert.crit_max {
                     lazy_format!(
-                        "{channel_name}: {channel_value} is greater than allowed maximum: {max}"
-                    ),
-                );
-            } else if channel_value < alert.min {
-                if alert.state == AlertState::Active {
-                    continue;
+                        "{channel_name}: {channel_value} is greater than the critical maximum: {}",
+                        alert.crit_max
+                    )
+                    .to_string()
+                } else {
+                    lazy_format!(
+                        "{channel_name}: {channel_value} is less than the critical minimum: {}",
+                        alert.crit_min
+                    )
+                    .to_string()
                 }
-                let channel_name = alert.channel_source.channel_name.clone();
-                let min = alert.min;
-                Self::activate_alert(
-                    &mut alerts_to_fire,
-                    alert,
+            } else if is_warning {
+                if alert
+                    .warn_max
+                    .map_or(false, |warn_max| channel_value > warn_max)
+                {
                     lazy_format!(
-                        "{channel_name}: {channel_value} is less than allowed minimum: {min}"
-                    ),
-                );
-            } else if alert.state != AlertState::Inactive {
-                let channel_name = alert.channel_source.channel_name.clone();
-                let min = alert.min;
-                let max = alert.max;
-                Self::deactivate_alert(
-                    &mut alerts_to_fire,
-                    alert,
-                    format!(
-                    "{channel_name}: {channel_value} is again within allowed range: {min} - {max}"
-                ),
-                );
-            }
+                        "{channel_name}: {channel_value} is greater than the warning maximum: {}",
+                        alert.warn_max.unwrap()
+                    )
+                    .to_string()
+                } else {
+                    lazy_format!(
+                        "{channel_name}: {channel_value} is less than the warning minimum: {}",
+                        alert.warn_min.unwrap()
+                    )
+                    .to_string()
+                }
+            } else {
+                format!(
+                    "{channel_name}: {channel_value} is again within allowed range: {} - {}",
+                    alert.warn_min.unwrap_or(alert.crit_min),
+                    alert.warn_max.unwrap_or(alert.crit_max)
+                )
+            };
+            self.transition_alert_state(&mut alerts_to_fire, alert, new_state, message);
         }
         alerts_to_fire
     }
 
-    /// Adds an Alert to the list of alerts to fire, if state has changed.
-    fn activate_alert(
+    /// Moves an Alert to `new_state` and adds it to the list of alerts to fire, if that's
+    /// actually a change from its current tier. Covers every tier transition
+    /// (`Inactive`<->`Warning`<->`Critical`), not just entering/exiting a single active state.
+    ///
+    /// Subject to `trigger_delay_secs`/`reset_delay_secs`: the channel value must stay in
+    /// `new_state`'s tier for the configured delay before the transition actually fires, so a
+    /// single noisy reading or a value hovering at a threshold doesn't flap the Alert. A reading
+    /// that settles back on the Alert's current tier before the delay elapses cancels the pending
+    /// transition.
+    fn transition_alert_state(
+        &self,
         alerts_to_fire: &mut Vec<(Alert, AlertLogMessage)>,
         alert: &mut Alert,
+        new_state: AlertState,
         message: impl Display,
     ) {
-        if alert.state == AlertState::Active {
-            return; // only fire on state change
+        if alert.state == new_state {
+            self.pending_transitions.borrow_mut().remove(&alert.uid);
+            return; // only fire on a tier change
+        }
+        let delay_secs = if new_state.severity() > alert.state.severity() {
+            alert.trigger_delay_secs
+        } else {
+            alert.reset_delay_secs
+        };
+        if let Some(delay_secs) = delay_secs {
+            let now = Instant::now();
+            let mut pending = self.pending_transitions.borrow_mut();
+            let is_due = match pending.get(&alert.uid) {
+                Some((pending_state, since)) if *pending_state == new_state => {
+                    now.duration_since(*since) >= Duration::from_secs(delay_secs)
+                }
+                _ => {
+                    pending.insert(alert.uid.clone(), (new_state, now));
+                    false
+                }
+            };
+            if is_due.not() {
+                return;
+            }
+            pending.remove(&alert.uid);
+        }
+        alert.state = new_state;
+        if self.is_snoozed(&alert.uid) {
+            return; // state updated internally, but suppressed while acknowledged
         }
-        alert.state = AlertState::Active;
         alerts_to_fire.push((alert.clone(), message.to_string()));
     }
 
-    /// Adds an Alert to the list of alerts to fire. State change should be checked before calling
-    /// this method.
-    fn deactivate_alert(
-        alerts_to_fire: &mut Vec<(Alert, AlertLogMessage)>,
-        alert: &mut Alert,
-        message: String,
-    ) {
-        alert.state = AlertState::Inactive;
+    /// Whether `uid` is currently acknowledged/snoozed and that snooze hasn't yet expired.
+    fn is_snoozed(&self, uid: &UID) -> bool {
+        self.snoozed_until
+            .borrow()
+            .get(uid)
+            .is_some_and(|until| Local::now() < *until)
+    }
+
+    /// If `alert`'s snooze has just expired and it's still not `Inactive`, removes the snooze and
+    /// logs a "still active" entry so the user isn't left thinking it cleared on its own.
+    fn rearm_if_snooze_expired(&self, alert: &Alert, alerts_to_fire: &mut Vec<(Alert, AlertLogMessage)>) {
+        if alert.state == AlertState::Inactive {
+            return;
+        }
+        let expired = self
+            .snoozed_until
+            .borrow()
+            .get(&alert.uid)
+            .is_some_and(|until| Local::now() >= *until);
+        if expired.not() {
+            return;
+        }
+        self.snoozed_until.borrow_mut().remove(&alert.uid);
+        let message = format!(
+            "{}: still {}",
+            alert.channel_source.channel_name, alert.state
+        );
         alerts_to_fire.push((alert.clone(), message));
     }
 
@@ -422,4 +921,9 @@ impl AlertController {
 struct AlertConfigFile {
     alerts: Vec<Alert>,
     logs: Vec<AlertLog>,
+    #[serde(default)]
+    notification_sinks: Vec<NotificationSinkConfig>,
+    /// Acknowledged/snoozed Alert uids and when each snooze expires.
+    #[serde(default)]
+    acknowledgements: HashMap<UID, DateTime<Local>>,
 }
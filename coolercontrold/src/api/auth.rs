@@ -0,0 +1,199 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2023  Guy Boldon
+ * |
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * |
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ * |
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use actix_web::body::EitherBody;
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::web::{Data, Json};
+use actix_web::{post, Error, HttpMessage, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::api::CCError;
+use crate::config::Config;
+use crate::setting::{Auth, CoolerControlSettings};
+
+const SESSION_TOKEN_HEADER: &str = "Authorization";
+
+/// Hashes a plain-text secret so that it is never stored or compared in the clear.
+pub fn hash_secret(secret: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(secret.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LoginRequest {
+    user: Option<String>,
+    secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LoginResponse {
+    token: String,
+}
+
+/// Issues a session token for clients that authenticate with credentials or a static token.
+/// The daemon keeps no session store; the returned token is the stored hash of the secret, so
+/// it can be verified the same way on every subsequent request and stays valid until the
+/// secret changes.
+#[post("/login")]
+async fn login(
+    login_request: Json<LoginRequest>,
+    config: Data<Arc<Config>>,
+) -> Result<impl Responder, CCError> {
+    let settings = config.get_settings().await?;
+    match settings.auth {
+        Auth::None => Ok(HttpResponse::Ok().json(Json(LoginResponse {
+            token: Uuid::new_v4().to_string(),
+        }))),
+        Auth::Credentials { user, secret_hash } => {
+            let given_user = login_request.user.clone().unwrap_or_default();
+            if given_user == user && hash_secret(&login_request.secret) == secret_hash {
+                Ok(HttpResponse::Ok().json(Json(LoginResponse {
+                    token: secret_hash,
+                })))
+            } else {
+                Err(CCError::UserError {
+                    msg: "Invalid credentials".to_string(),
+                })
+            }
+        }
+        Auth::Token { secret_hash } => {
+            if hash_secret(&login_request.secret) == secret_hash {
+                Ok(HttpResponse::Ok().json(Json(LoginResponse {
+                    token: secret_hash,
+                })))
+            } else {
+                Err(CCError::UserError {
+                    msg: "Invalid token".to_string(),
+                })
+            }
+        }
+    }
+}
+
+pub fn login_service() -> impl actix_web::dev::HttpServiceFactory {
+    login
+}
+
+/// Middleware that rejects any request missing a valid `Authorization` header, unless the
+/// configured `Auth` mode is `None` (the default, which preserves the previous, unauthenticated
+/// behavior).
+pub struct AuthGuard {
+    pub config: Arc<Config>,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for AuthGuard
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = AuthGuardMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthGuardMiddleware {
+            service: Rc::new(service),
+            config: self.config.clone(),
+        }))
+    }
+}
+
+pub struct AuthGuardMiddleware<S> {
+    service: Rc<S>,
+    config: Arc<Config>,
+}
+
+impl<S, B> Service<ServiceRequest> for AuthGuardMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let config = self.config.clone();
+        if req.path() == "/handshake" || req.path() == "/login" {
+            return Box::pin(async move { Ok(service.call(req).await?.map_into_left_body()) });
+        }
+        Box::pin(async move {
+            let settings = match config.get_settings().await {
+                Ok(settings) => settings,
+                Err(err) => {
+                    let response = HttpResponse::from_error(
+                        CCError::InternalError {
+                            msg: err.to_string(),
+                        }
+                        .into(),
+                    );
+                    return Ok(req.into_response(response).map_into_right_body());
+                }
+            };
+            let expected_secret_hash = match &settings.auth {
+                Auth::None => None,
+                Auth::Credentials { secret_hash, .. } | Auth::Token { secret_hash } => {
+                    Some(secret_hash.clone())
+                }
+            };
+            let Some(expected_secret_hash) = expected_secret_hash else {
+                let res = service.call(req).await?;
+                return Ok(res.map_into_left_body());
+            };
+            let provided = req
+                .headers()
+                .get(SESSION_TOKEN_HEADER)
+                .and_then(|header| header.to_str().ok())
+                .map(|header| header.trim_start_matches("Bearer ").to_string());
+            // Constant-time compare: this is the long-lived static secret hash, so a
+            // timing-dependent `==` would leak how many leading bytes an attacker has guessed.
+            let is_authorized = provided
+                .as_deref()
+                .map(|provided| bool::from(provided.as_bytes().ct_eq(expected_secret_hash.as_bytes())))
+                .unwrap_or(false);
+            if is_authorized {
+                let res = service.call(req).await?;
+                Ok(res.map_into_left_body())
+            } else {
+                let response = HttpResponse::from_error(
+                    CCError::UserError {
+                        msg: "Unauthorized".to_string(),
+                    }
+                    .into(),
+                );
+                Ok(req.into_response(response).map_into_right_body())
+            }
+        })
+    }
+}
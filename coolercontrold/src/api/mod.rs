@@ -42,6 +42,8 @@ mod status;
 mod settings;
 mod profiles;
 mod functions;
+pub mod auth;
+pub mod ws;
 
 const GUI_SERVER_PORT: u16 = 11987;
 const GUI_SERVER_ADDR: &str = "127.0.0.1";
@@ -139,6 +141,7 @@ fn handle_simple_result(result: Result<()>) -> HttpResponse {
 }
 
 pub async fn init_server(all_devices: AllDevices, settings_processor: Arc<SettingsProcessor>, config: Arc<Config>) -> Result<Server> {
+    let status_broadcaster = Arc::new(ws::StatusBroadcaster::new());
     let server = HttpServer::new(move || {
         App::new()
             .wrap(Condition::new(
@@ -160,7 +163,13 @@ pub async fn init_server(all_devices: AllDevices, settings_processor: Arc<Settin
             .app_data(Data::new(all_devices.clone()))
             .app_data(Data::new(settings_processor.clone()))
             .app_data(Data::new(config.clone()))
+            .app_data(Data::new(status_broadcaster.clone()))
+            .wrap(auth::AuthGuard {
+                config: config.clone(),
+            })
             .service(handshake)
+            .service(auth::login_service())
+            .service(ws::status_ws)
             .service(shutdown)
             .service(thinkpad_fan_control)
             .service(devices::get_devices)
@@ -179,6 +188,9 @@ pub async fn init_server(all_devices: AllDevices, settings_processor: Arc<Settin
             .service(devices::asetek)
             .service(settings::get_cc_settings)
             .service(settings::apply_cc_settings)
+            .service(settings::get_backups)
+            .service(settings::create_backup)
+            .service(settings::restore_backup)
             .service(profiles::get_profiles)
             .service(profiles::save_profiles_order)
             .service(profiles::save_profile)
@@ -18,13 +18,18 @@
 
 use crate::api::auth::verify_admin_permissions;
 use crate::api::{handle_error, validate_name_string, AppState, CCError};
-use crate::setting::{Profile, ProfileType};
+use crate::setting::{Function, Profile, ProfileType, ProfileVariant};
 use aide::NoApi;
 use axum_jsonschema::Json;
 use axum::extract::{Path, State};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tower_sessions::Session;
+use uuid::Uuid;
+
+/// The current version of the [`ProfilePackage`] export format. Bumped whenever the package
+/// shape changes in a way that older daemons wouldn't understand.
+const PROFILE_PACKAGE_SCHEMA_VERSION: u8 = 1;
 
 /// Retrieves the persisted Profile list
 pub async fn get_all(
@@ -90,7 +95,158 @@ fn validate_profile(profile: &Profile) -> Result<(), CCError> {
     Ok(())
 }
 
+/// Bundles a Profile with every Function it references (its own `function_uid` plus each
+/// variant's) so that the pair can be shared and re-imported as a single, self-contained unit.
+pub async fn export(
+    Path(profile_uid): Path<String>,
+    State(AppState {
+        profile_handle,
+        function_handle,
+        ..
+    }): State<AppState>,
+) -> Result<Json<ProfilePackage>, CCError> {
+    let profile = profile_handle
+        .get_all()
+        .await
+        .map_err(handle_error)?
+        .into_iter()
+        .find(|profile| profile.uid == profile_uid)
+        .ok_or_else(|| CCError::NotFound {
+            msg: format!("Profile {profile_uid} not found"),
+        })?;
+    let referenced_function_uids = referenced_function_uids(&profile);
+    let all_functions = function_handle.get_all().await.map_err(handle_error)?;
+    let functions = all_functions
+        .into_iter()
+        .filter(|function| referenced_function_uids.contains(&function.uid))
+        .collect();
+    Ok(Json(ProfilePackage {
+        schema_version: PROFILE_PACKAGE_SCHEMA_VERSION,
+        profile,
+        functions,
+    }))
+}
+
+/// Imports a previously exported Profile package, giving its Profile (and every bundled Function
+/// it creates) a fresh uid so the import never clobbers existing data. Functions referenced by
+/// the package but not already present are created under a new uid; functions that already
+/// exist by uid are left untouched and the imported Profile keeps referencing them.
+pub async fn import(
+    NoApi(session): NoApi<Session>,
+    State(AppState {
+        profile_handle,
+        function_handle,
+        ..
+    }): State<AppState>,
+    Json(package): Json<ProfilePackage>,
+) -> Result<Json<Profile>, CCError> {
+    verify_admin_permissions(&session).await?;
+    if package.schema_version != PROFILE_PACKAGE_SCHEMA_VERSION {
+        return Err(CCError::UserError {
+            msg: format!(
+                "Unsupported profile package schema version: {}",
+                package.schema_version
+            ),
+        });
+    }
+    let existing_function_uids: std::collections::HashSet<String> = function_handle
+        .get_all()
+        .await
+        .map_err(handle_error)?
+        .into_iter()
+        .map(|function| function.uid)
+        .collect();
+    let mut function_uid_remap = std::collections::HashMap::new();
+    for mut function in package.functions {
+        if existing_function_uids.contains(&function.uid) {
+            continue;
+        }
+        let imported_uid = Uuid::new_v4().to_string();
+        function_uid_remap.insert(function.uid.clone(), imported_uid.clone());
+        function.uid = imported_uid;
+        function_handle
+            .create(function)
+            .await
+            .map_err(handle_error)?;
+    }
+    let mut profile = package.profile;
+    profile.uid = Uuid::new_v4().to_string();
+    remap_function_uid(&mut profile.function_uid, &function_uid_remap);
+    for variant in profile.variants.values_mut() {
+        remap_function_uid(&mut variant.function_uid, &function_uid_remap);
+    }
+    validate_profile(&profile)?;
+    profile_handle
+        .create(profile.clone())
+        .await
+        .map_err(handle_error)?;
+    Ok(Json(profile))
+}
+
+/// Switches which variant's settings are currently active for a Profile, without touching the
+/// Profile's other variants or needing the caller to resend the whole Profile.
+pub async fn set_active_variant(
+    Path((profile_uid, variant_name)): Path<(String, String)>,
+    NoApi(session): NoApi<Session>,
+    State(AppState { profile_handle, .. }): State<AppState>,
+) -> Result<(), CCError> {
+    verify_admin_permissions(&session).await?;
+    let mut profile = profile_handle
+        .get_all()
+        .await
+        .map_err(handle_error)?
+        .into_iter()
+        .find(|profile| profile.uid == profile_uid)
+        .ok_or_else(|| CCError::NotFound {
+            msg: format!("Profile {profile_uid} not found"),
+        })?;
+    let variant = profile
+        .variants
+        .get(&variant_name)
+        .ok_or_else(|| CCError::NotFound {
+            msg: format!("Profile {profile_uid} has no variant named '{variant_name}'"),
+        })?
+        .clone();
+    apply_variant(&mut profile, &variant_name, variant);
+    profile_handle.update(profile).await.map_err(handle_error)
+}
+
+/// Returns the set of Function uids a Profile relies on, across its base settings and every
+/// variant.
+fn referenced_function_uids(profile: &Profile) -> std::collections::HashSet<String> {
+    let mut uids: std::collections::HashSet<String> = profile
+        .variants
+        .values()
+        .map(|variant| variant.function_uid.clone())
+        .collect();
+    uids.insert(profile.function_uid.clone());
+    uids
+}
+
+fn remap_function_uid(function_uid: &mut String, remap: &std::collections::HashMap<String, String>) {
+    if let Some(imported_uid) = remap.get(function_uid) {
+        *function_uid = imported_uid.clone();
+    }
+}
+
+/// Copies a variant's settings into the Profile's base fields and records it as active.
+fn apply_variant(profile: &mut Profile, variant_name: &str, variant: ProfileVariant) {
+    profile.speed_fixed = variant.speed_fixed;
+    profile.speed_profile = variant.speed_profile;
+    profile.temp_source = variant.temp_source;
+    profile.function_uid = variant.function_uid;
+    profile.active_variant = Some(variant_name.to_string());
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProfilesDto {
     profiles: Vec<Profile>,
 }
+
+/// A self-contained, shareable bundle of a Profile and every Function it references.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProfilePackage {
+    schema_version: u8,
+    profile: Profile,
+    functions: Vec<Function>,
+}
@@ -21,13 +21,15 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use actix_web::web::{Data, Json, Path};
-use actix_web::{get, patch, put, HttpResponse, Responder};
+use actix_web::{get, patch, post, put, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 
 use crate::api::{handle_error, handle_simple_result, CCError};
-use crate::config::Config;
+use crate::config::{Config, ConfigBackupMetadata};
 use crate::device::UID;
-use crate::setting::{CoolerControlDeviceSettings, CoolerControlSettings};
+use std::str::FromStr;
+
+use crate::setting::{Auth, CoolerControlDeviceSettings, CoolerControlSettings, TempUnit};
 use crate::AllDevices;
 
 /// Get General CoolerControl settings
@@ -46,14 +48,17 @@ async fn apply_cc_settings(
     cc_settings_request: Json<CoolerControlSettingsDto>,
     config: Data<Arc<Config>>,
 ) -> Result<impl Responder, CCError> {
-    handle_simple_result(match config.get_settings().await {
-        Ok(current_settings) => {
-            let settings_to_set = cc_settings_request.merge(current_settings);
-            config.set_settings(&settings_to_set).await;
-            config.save_config_file().await
-        }
-        Err(err) => Err(err),
-    })
+    let current_settings = config
+        .get_settings()
+        .await
+        .map_err(|err| <anyhow::Error as Into<CCError>>::into(err))?;
+    let settings_to_set = cc_settings_request.merge(current_settings)?;
+    config.set_settings(&settings_to_set).await;
+    config
+        .save_config_file()
+        .await
+        .map_err(|err| <anyhow::Error as Into<CCError>>::into(err))?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"success": true})))
 }
 
 /// Get All CoolerControl settings that apply to a specific Device
@@ -180,6 +185,48 @@ async fn save_ui_settings(
     handle_simple_result(config.save_ui_config_file(&ui_settings_request).await)
 }
 
+/// Lists all stored configuration backups, most recent first.
+#[get("/settings/backups")]
+async fn get_backups(config: Data<Arc<Config>>) -> Result<impl Responder, CCError> {
+    config
+        .list_backups()
+        .await
+        .map(|backups| HttpResponse::Ok().json(Json(ConfigBackupsDto { backups })))
+        .map_err(|err| err.into())
+}
+
+/// Captures the current daemon config + UI config as a named, timestamped snapshot.
+#[post("/settings/backups")]
+async fn create_backup(
+    backup_request: Json<CreateBackupRequestDto>,
+    config: Data<Arc<Config>>,
+) -> Result<impl Responder, CCError> {
+    config
+        .create_backup(backup_request.into_inner().name)
+        .await
+        .map(|metadata| HttpResponse::Ok().json(Json(metadata)))
+        .map_err(|err| err.into())
+}
+
+/// Atomically swaps the live config to the given backup and reloads it into memory.
+#[post("/settings/backups/{id}/restore")]
+async fn restore_backup(
+    id: Path<String>,
+    config: Data<Arc<Config>>,
+) -> Result<impl Responder, CCError> {
+    handle_simple_result(config.restore_backup(&id).await)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CreateBackupRequestDto {
+    name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConfigBackupsDto {
+    backups: Vec<ConfigBackupMetadata>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CoolerControlSettingsDto {
     apply_on_boot: Option<bool>,
@@ -187,10 +234,31 @@ struct CoolerControlSettingsDto {
     startup_delay: Option<u8>,
     smoothing_level: Option<u8>,
     thinkpad_full_speed: Option<bool>,
+
+    /// Skip polling devices that are sysfs-runtime-suspended, reusing their last cached status.
+    power_aware_polling: Option<bool>,
+
+    /// "Celsius", "Fahrenheit" or "Kelvin". Applies only at the API boundary; internal storage
+    /// and profile math always stay Celsius.
+    temp_unit: Option<String>,
+
+    /// The base polling period, in milliseconds, for repos/devices that don't declare their own
+    /// override. Clamped to a sane minimum so a typo can't busy-loop the daemon.
+    tick_rate_ms: Option<u64>,
+
+    /// "None", "Credentials" or "Token". Leave unset to keep the current mode.
+    auth_mode: Option<String>,
+
+    /// Required when `auth_mode` is "Credentials".
+    auth_user: Option<String>,
+
+    /// The plain-text secret to hash and persist. Required when `auth_mode` is "Credentials"
+    /// or "Token"; ignored otherwise. Never echoed back in responses.
+    auth_secret: Option<String>,
 }
 
 impl CoolerControlSettingsDto {
-    fn merge(&self, current_settings: CoolerControlSettings) -> CoolerControlSettings {
+    fn merge(&self, current_settings: CoolerControlSettings) -> Result<CoolerControlSettings, CCError> {
         let apply_on_boot = if let Some(apply) = self.apply_on_boot {
             apply
         } else {
@@ -216,25 +284,110 @@ impl CoolerControlSettingsDto {
         } else {
             current_settings.thinkpad_full_speed
         };
-        CoolerControlSettings {
+        let power_aware_polling = if let Some(enabled) = self.power_aware_polling {
+            enabled
+        } else {
+            current_settings.power_aware_polling
+        };
+        let temp_unit = if let Some(unit_str) = &self.temp_unit {
+            TempUnit::from_str(unit_str).unwrap_or(current_settings.temp_unit)
+        } else {
+            current_settings.temp_unit
+        };
+        let tick_rate_ms = if let Some(rate) = self.tick_rate_ms {
+            rate.max(100)
+        } else {
+            current_settings.tick_rate_ms
+        };
+        let auth = self.merge_auth(current_settings.auth)?;
+        Ok(CoolerControlSettings {
             apply_on_boot,
             no_init: current_settings.no_init,
             handle_dynamic_temps,
             startup_delay,
             smoothing_level,
             thinkpad_full_speed,
-        }
+            power_aware_polling,
+            auth,
+            temp_unit,
+            tick_rate_ms,
+            temp_filter: current_settings.temp_filter,
+        })
+    }
+
+    /// Only replaces the `Auth` mode when the request explicitly sets `auth_mode`, hashing the
+    /// given secret so the plain-text value is never written to the configuration file.
+    ///
+    /// `auth_secret` is never echoed back in responses, so a plain resubmit of a previously
+    /// fetched settings object omits it. When the mode is unchanged from `current_auth`, the
+    /// existing `secret_hash` is preserved rather than re-hashed from an absent secret. When the
+    /// mode actually changes to `Credentials`/`Token` without a secret, that's a user error: we'd
+    /// otherwise overwrite a working credential with the hash of an empty string.
+    fn merge_auth(&self, current_auth: Auth) -> Result<Auth, CCError> {
+        let Some(mode) = &self.auth_mode else {
+            return Ok(current_auth);
+        };
+        Ok(match mode.as_str() {
+            "None" => Auth::None,
+            "Credentials" => Auth::Credentials {
+                user: self.auth_user.clone().unwrap_or_default(),
+                secret_hash: match &self.auth_secret {
+                    Some(secret) => crate::api::auth::hash_secret(secret),
+                    None => match &current_auth {
+                        Auth::Credentials { secret_hash, .. } => secret_hash.clone(),
+                        _ => {
+                            return Err(CCError::UserError {
+                                msg: "auth_secret is required when setting auth_mode to Credentials".to_string(),
+                            })
+                        }
+                    },
+                },
+            },
+            "Token" => Auth::Token {
+                secret_hash: match &self.auth_secret {
+                    Some(secret) => crate::api::auth::hash_secret(secret),
+                    None => match &current_auth {
+                        Auth::Token { secret_hash } => secret_hash.clone(),
+                        _ => {
+                            return Err(CCError::UserError {
+                                msg: "auth_secret is required when setting auth_mode to Token".to_string(),
+                            })
+                        }
+                    },
+                },
+            },
+            _ => current_auth,
+        })
     }
 }
 
 impl From<&CoolerControlSettings> for CoolerControlSettingsDto {
     fn from(settings: &CoolerControlSettings) -> Self {
+        let auth_mode = Some(
+            match &settings.auth {
+                Auth::None => "None",
+                Auth::Credentials { .. } => "Credentials",
+                Auth::Token { .. } => "Token",
+            }
+            .to_string(),
+        );
+        let auth_user = match &settings.auth {
+            Auth::Credentials { user, .. } => Some(user.clone()),
+            _ => None,
+        };
         Self {
             apply_on_boot: Some(settings.apply_on_boot),
             handle_dynamic_temps: Some(settings.handle_dynamic_temps),
             startup_delay: Some(settings.startup_delay.as_secs() as u8),
             smoothing_level: Some(settings.smoothing_level),
             thinkpad_full_speed: Some(settings.thinkpad_full_speed),
+            power_aware_polling: Some(settings.power_aware_polling),
+            temp_unit: Some(settings.temp_unit.to_string()),
+            tick_rate_ms: Some(settings.tick_rate_ms),
+            auth_mode,
+            auth_user,
+            // the secret hash is never returned to the client
+            auth_secret: None,
         }
     }
 }
@@ -0,0 +1,230 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2023  Guy Boldon
+ * |
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ * |
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ * |
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix::{Actor, ActorContext, ActorFutureExt, AsyncContext, Message, StreamHandler, WrapFuture};
+use actix_web::web::{Data, Payload};
+use actix_web::{get, Error, HttpRequest, HttpResponse};
+use actix_web_actors::ws;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::device::{Status, UID};
+use crate::AllDevices;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The full current status of a single device, broadcast to subscribed WebSocket clients every
+/// time the main loop completes a poll cycle.
+#[derive(Debug, Clone, Serialize, Deserialize, Message)]
+#[rtype(result = "()")]
+pub struct DeviceStatusDelta {
+    pub device_uid: UID,
+    pub status: Status,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StatusFrame<'a> {
+    device_uid: &'a UID,
+    status: &'a Status,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum ClientControlMessage {
+    Subscribe { device_uids: Vec<UID> },
+    Unsubscribe { device_uids: Vec<UID> },
+}
+
+/// Broadcasts device status deltas to all connected WebSocket clients. Held as `Data` so the
+/// poll loop (which completes each cycle in `main_loop.rs`) can publish without needing to know
+/// which, or how many, clients are currently connected.
+pub struct StatusBroadcaster {
+    sender: broadcast::Sender<DeviceStatusDelta>,
+}
+
+impl StatusBroadcaster {
+    pub fn new() -> Self {
+        // capacity is generous: a dropped message just means a slow client misses one delta,
+        // it will receive the next one on the following poll cycle.
+        let (sender, _) = broadcast::channel(256);
+        Self { sender }
+    }
+
+    /// Called once per device, per poll cycle, after `StatusHandle::broadcast_status` would
+    /// otherwise only update in-process subscribers.
+    pub fn publish(&self, device_uid: UID, status: Status) {
+        let _ = self.sender.send(DeviceStatusDelta { device_uid, status });
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<DeviceStatusDelta> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for StatusBroadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct StatusSocket {
+    all_devices: AllDevices,
+    broadcaster: Arc<StatusBroadcaster>,
+    subscribed_uids: HashSet<UID>,
+    last_heartbeat: Instant,
+}
+
+impl StatusSocket {
+    fn new(all_devices: AllDevices, broadcaster: Arc<StatusBroadcaster>) -> Self {
+        Self {
+            all_devices,
+            broadcaster,
+            subscribed_uids: HashSet::new(),
+            last_heartbeat: Instant::now(),
+        }
+    }
+
+    fn is_subscribed(&self, device_uid: &UID) -> bool {
+        self.subscribed_uids.is_empty() || self.subscribed_uids.contains(device_uid)
+    }
+
+    fn start_heartbeat(ctx: &mut ws::WebsocketContext<Self>) {
+        ctx.run_interval(HEARTBEAT_INTERVAL, |socket, ctx| {
+            if Instant::now().duration_since(socket.last_heartbeat) > CLIENT_TIMEOUT {
+                ctx.stop();
+                return;
+            }
+            ctx.ping(b"");
+        });
+    }
+
+    fn start_status_relay(&self, ctx: &mut ws::WebsocketContext<Self>) {
+        let mut receiver = self.broadcaster.subscribe();
+        ctx.add_stream(async_stream::stream! {
+            loop {
+                match receiver.recv().await {
+                    Ok(delta) => yield delta,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+impl Actor for StatusSocket {
+    type Context = ws::WebsocketContext<Self>;
+
+    /// Sends one snapshot of all current device statuses on connect, then begins streaming
+    /// each device's full status as the main loop publishes it.
+    fn started(&mut self, ctx: &mut Self::Context) {
+        Self::start_heartbeat(ctx);
+        self.start_status_relay(ctx);
+        let all_devices = self.all_devices.clone();
+        ctx.wait(
+            async move {
+                let mut snapshot = Vec::new();
+                for (device_uid, device) in all_devices.iter() {
+                    if let Some(status) = device.read().await.status_current() {
+                        snapshot.push((device_uid.clone(), status));
+                    }
+                }
+                snapshot
+            }
+            .into_actor(self)
+            .map(|snapshot, _socket, ctx| {
+                for (device_uid, status) in &snapshot {
+                    let frame = StatusFrame {
+                        device_uid,
+                        status,
+                    };
+                    if let Ok(json) = serde_json::to_string(&frame) {
+                        ctx.text(json);
+                    }
+                }
+            }),
+        );
+    }
+}
+
+impl StreamHandler<DeviceStatusDelta> for StatusSocket {
+    fn handle(&mut self, delta: DeviceStatusDelta, ctx: &mut Self::Context) {
+        if self.is_subscribed(&delta.device_uid) {
+            let frame = StatusFrame {
+                device_uid: &delta.device_uid,
+                status: &delta.status,
+            };
+            if let Ok(json) = serde_json::to_string(&frame) {
+                ctx.text(json);
+            }
+        }
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for StatusSocket {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            Ok(ws::Message::Ping(msg)) => {
+                self.last_heartbeat = Instant::now();
+                ctx.pong(&msg);
+            }
+            Ok(ws::Message::Pong(_)) => {
+                self.last_heartbeat = Instant::now();
+            }
+            Ok(ws::Message::Text(text)) => {
+                match serde_json::from_str::<ClientControlMessage>(&text) {
+                    Ok(ClientControlMessage::Subscribe { device_uids }) => {
+                        self.subscribed_uids.extend(device_uids);
+                    }
+                    Ok(ClientControlMessage::Unsubscribe { device_uids }) => {
+                        for uid in &device_uids {
+                            self.subscribed_uids.remove(uid);
+                        }
+                    }
+                    Err(err) => log::warn!("Invalid status WebSocket control message: {err}"),
+                }
+            }
+            Ok(ws::Message::Close(reason)) => {
+                ctx.close(reason);
+                ctx.stop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Streams live device status over a WebSocket, pushing one snapshot on connect and then each
+/// device's full current status after every poll cycle; unchanged channels are sent again too.
+#[get("/ws/status")]
+pub async fn status_ws(
+    req: HttpRequest,
+    stream: Payload,
+    all_devices: Data<AllDevices>,
+    broadcaster: Data<Arc<StatusBroadcaster>>,
+) -> Result<HttpResponse, Error> {
+    ws::start(
+        StatusSocket::new(all_devices.get_ref().clone(), broadcaster.get_ref().clone()),
+        &req,
+        stream,
+    )
+}
@@ -0,0 +1,219 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2021-2024  Guy Boldon, Eren Simsek and contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! An injectable clock/time source, so `main_loop::run`'s scheduling contract (full-second
+//! alignment, every-other-tick LCD updates, snapshot timeouts, `wake_from_sleep`'s
+//! `startup_delay`) can be exercised deterministically: the production `TokioClock` delegates to
+//! real wall-clock time, while `VirtualClock` advances only when told to, so a caller can assert
+//! on scheduling behavior without any wall-clock waits.
+
+use std::collections::BinaryHeap;
+use std::cmp::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::oneshot;
+
+/// Returned by [`with_timeout`] when `duration` elapses before the inner future completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed;
+
+/// A source of "now" and a way to sleep until a future instant. Implemented by `TokioClock` for
+/// production use and `VirtualClock` for deterministic tests.
+#[async_trait(?Send)]
+pub trait Clock {
+    fn now(&self) -> Instant;
+    async fn sleep_until(&self, deadline: Instant);
+
+    async fn sleep(&self, duration: Duration) {
+        self.sleep_until(self.now() + duration).await;
+    }
+}
+
+/// Races `future` against `clock.sleep(duration)`, returning `Elapsed` if the sleep wins.
+/// Clock-generic equivalent of `tokio::time::timeout`.
+pub async fn with_timeout<C, F, T>(clock: &C, duration: Duration, future: F) -> Result<T, Elapsed>
+where
+    C: Clock + ?Sized,
+    F: std::future::Future<Output = T>,
+{
+    tokio::select! {
+        result = future => Ok(result),
+        () = clock.sleep(duration) => Err(Elapsed),
+    }
+}
+
+/// Delegates to real wall-clock time via Tokio's own timers.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioClock;
+
+#[async_trait(?Send)]
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        tokio::time::sleep_until(deadline.into()).await;
+    }
+}
+
+struct Waiter {
+    deadline: Instant,
+    notify: Option<oneshot::Sender<()>>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+impl Eq for Waiter {}
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (a max-heap) pops the *earliest* deadline first.
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+/// A virtual clock for tests: `now()` only moves forward when [`VirtualClock::advance`] is
+/// called, and any pending `sleep_until` waiters whose deadline has passed are woken at that
+/// point - no real time passes while a test awaits a `VirtualClock` sleep.
+pub struct VirtualClock {
+    state: Mutex<VirtualClockState>,
+}
+
+struct VirtualClockState {
+    now: Instant,
+    waiters: BinaryHeap<Waiter>,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(VirtualClockState {
+                now: Instant::now(),
+                waiters: BinaryHeap::new(),
+            }),
+        }
+    }
+
+    /// Moves virtual time forward by `duration`, waking any waiter whose deadline is now due.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().expect("VirtualClock mutex poisoned");
+        state.now += duration;
+        let now = state.now;
+        while let Some(waiter) = state.waiters.peek() {
+            if waiter.deadline > now {
+                break;
+            }
+            let mut waiter = state.waiters.pop().expect("peeked waiter must pop");
+            if let Some(notify) = waiter.notify.take() {
+                let _ = notify.send(());
+            }
+        }
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait(?Send)]
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        self.state.lock().expect("VirtualClock mutex poisoned").now
+    }
+
+    async fn sleep_until(&self, deadline: Instant) {
+        let receiver = {
+            let mut state = self.state.lock().expect("VirtualClock mutex poisoned");
+            if deadline <= state.now {
+                return;
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.waiters.push(Waiter {
+                deadline,
+                notify: Some(sender),
+            });
+            receiver
+        };
+        let _ = receiver.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn advance_does_not_wake_a_waiter_before_its_deadline() {
+        let clock = Arc::new(VirtualClock::new());
+        let sleeper_clock = clock.clone();
+        let woken = Arc::new(std::sync::Mutex::new(false));
+        let sleeper_woken = woken.clone();
+        // `Clock` futures are not `Send`, so the sleeper runs as a local task.
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async move {
+                let sleep_task = tokio::task::spawn_local(async move {
+                    sleeper_clock.sleep(Duration::from_millis(500)).await;
+                    *sleeper_woken.lock().unwrap() = true;
+                });
+                // Give the spawned task a chance to register its waiter before we advance.
+                tokio::task::yield_now().await;
+                clock.advance(Duration::from_millis(499));
+                tokio::task::yield_now().await;
+                assert!(!*woken.lock().unwrap(), "waiter fired before its deadline");
+                clock.advance(Duration::from_millis(1));
+                sleep_task.await.unwrap();
+                assert!(*woken.lock().unwrap(), "waiter never fired once its deadline passed");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn with_timeout_returns_the_future_result_when_it_resolves_first() {
+        let clock = VirtualClock::new();
+        let result = with_timeout(&clock, Duration::from_secs(10), async { 42 }).await;
+        assert_eq!(result, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn with_timeout_elapses_once_virtual_time_reaches_the_duration() {
+        let clock = Arc::new(VirtualClock::new());
+        let advancing_clock = clock.clone();
+        tokio::spawn(async move {
+            // A short real sleep purely to sequence after with_timeout has started polling;
+            // the timeout itself is still driven entirely by virtual time below.
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            advancing_clock.advance(Duration::from_millis(50));
+        });
+        let result = with_timeout(clock.as_ref(), Duration::from_millis(50), std::future::pending::<()>()).await;
+        assert_eq!(result, Err(Elapsed));
+    }
+}
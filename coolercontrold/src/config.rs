@@ -17,30 +17,225 @@
  */
 
 use std::collections::HashMap;
+use std::env;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Local};
 use const_format::concatcp;
 use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
 use toml_edit::{ArrayOfTables, Document, Formatted, Item, Table, Value};
+use uuid::Uuid;
 
+use crate::api::CCError;
 use crate::device::UID;
 use crate::repositories::repository::DeviceLock;
-use crate::setting::{CoolerControlDeviceSettings, CoolerControlSettings, Function, FunctionType, LcdSettings, LightingSettings, Profile, ProfileType, Setting, TempSource};
+use crate::setting::{Auth, CoolerControlDeviceSettings, CoolerControlSettings, Filter, Function, FunctionType, kelvin_to_rgb, LcdSettings, LightingSettings, Profile, ProfileType, Setting, TempSource, TempUnit};
 
 pub const DEFAULT_CONFIG_DIR: &str = "/etc/coolercontrol";
 const DEFAULT_CONFIG_FILE_PATH: &str = concatcp!(DEFAULT_CONFIG_DIR, "/config.toml");
 const DEFAULT_UI_CONFIG_FILE_PATH: &str = concatcp!(DEFAULT_CONFIG_DIR, "/config-ui.json");
 const DEFAULT_CONFIG_FILE_BYTES: &[u8] = include_bytes!("../resources/config-default.toml");
 
+/// Directory scanned, in lexical filename order, for drop-in config fragments layered over the
+/// base `config.toml` on every read - e.g. machine-provisioned defaults or per-profile overlays a
+/// packager can drop in without touching the user's main file. Writes always target the base file
+/// only, so fragment content is never duplicated back into it.
+const CONFIG_D_DIR: &str = concatcp!(DEFAULT_CONFIG_DIR, "/config.d");
+
+/// Extension (with a timestamp inserted before it) given to the previous good config file that
+/// `save_config_file` keeps alongside the main one before atomically replacing it.
+const CONFIG_BAK_EXTENSION: &str = "bak";
+
+/// How many `config.toml.<ts>.bak` sibling files `save_config_file` keeps before pruning the
+/// oldest - it's called on every routine config mutation, not just user-initiated ones, so
+/// without a cap these would accumulate for the life of the daemon.
+const MAX_RETAINED_CONFIG_BAKS: usize = 10;
+
+/// Bumped whenever the on-disk config format changes in a way that an older backup wouldn't
+/// restore correctly.
+const CONFIG_SCHEMA_VERSION: u32 = 1;
+
+/// An ordered chain of migration functions, each rewriting the `Document` from its index (the
+/// "from" version) up to index + 1. Operating on the `Document` directly, rather than a typed
+/// struct, means comments and formatting in untouched sections survive a migration. To bump
+/// `CONFIG_SCHEMA_VERSION`, append the new `N -> N+1` function here in the same commit.
+type Migration = fn(&mut Document);
+
+const MIGRATIONS: &[Migration] = &[
+    migrate_v0_to_v1,
+];
+
+/// Pre-versioning configuration files have no `version` key at all; stamping one in via
+/// [`migrate_document`] is the only change needed to bring them up to version 1.
+fn migrate_v0_to_v1(_document: &mut Document) {}
+
+/// Reads the stored `version` key (defaulting to 0 for pre-versioning configuration files), then
+/// runs every migration from that version up to `CONFIG_SCHEMA_VERSION` in order, logging and
+/// stamping the new version after each step. Returns whether any migration ran, so the caller
+/// knows whether the migrated document needs to be persisted.
+fn migrate_document(document: &mut Document) -> Result<bool> {
+    let mut version = document
+        .get("version")
+        .and_then(Item::as_integer)
+        .unwrap_or(0) as u32;
+    if version > CONFIG_SCHEMA_VERSION {
+        bail!(
+            "Configuration file schema version {version} is newer than this daemon supports \
+             (schema version {CONFIG_SCHEMA_VERSION}). Refusing to load a config written by a \
+             newer version of coolercontrold."
+        );
+    }
+    let starting_version = version;
+    while version < CONFIG_SCHEMA_VERSION {
+        let migration = MIGRATIONS.get(version as usize)
+            .with_context(|| format!("No migration registered from config schema version {version}"))?;
+        info!("Migrating configuration file from schema version {version} to {}", version + 1);
+        migration(document);
+        version += 1;
+        document["version"] = Item::Value(Value::Integer(Formatted::new(i64::from(version))));
+    }
+    Ok(version != starting_version)
+}
+/// Bumped whenever the shape of a single `[[functions]]` table changes in a way that needs a
+/// migration step (renaming a key, splitting/merging fields), independent of the whole-file
+/// `CONFIG_SCHEMA_VERSION`.
+const FUNCTION_SCHEMA_VERSION: u32 = 1;
+
+/// An ordered chain of per-function migration steps, mirroring [`Migration`]/[`MIGRATIONS`] but
+/// scoped to a single `[[functions]]` table. Each step transforms the table from its index (the
+/// "from" version) up to index + 1, and must be idempotent since [`Config::migrate_function_table`]
+/// is free to be called more than once on an already-migrated table.
+type FunctionMigration = fn(&mut Table);
+
+const FUNCTION_MIGRATIONS: &[FunctionMigration] = &[
+    migrate_function_v0_to_v1,
+];
+
+/// Pre-versioning function tables have no `version` key; stamping one in via
+/// [`Config::migrate_function_table`] is the only change needed to bring them up to version 1.
+fn migrate_function_v0_to_v1(_function_table: &mut Table) {}
+
+const BACKUP_DIR_NAME: &str = "backups";
+const BACKUP_CONFIG_FILE_NAME: &str = "config.toml";
+const BACKUP_UI_CONFIG_FILE_NAME: &str = "config-ui.json";
+const BACKUP_METADATA_FILE_NAME: &str = "metadata.json";
+/// How many config backups to keep before pruning the oldest.
+const MAX_RETAINED_BACKUPS: usize = 10;
+
+/// Metadata describing a single stored configuration backup. See [`Config::create_backup`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBackupMetadata {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Local>,
+    pub schema_version: u32,
+}
+
+/// Mirrors the fixed-speed/profile/temp-source trio shared by both a channel `Setting` and a
+/// `Profile`, so either can be deserialized from its own TOML table in one shot instead of walked
+/// field-by-field with `.with_context()` on every step.
+#[derive(Debug, Deserialize)]
+struct SpeedAndTempFields {
+    speed_fixed: Option<u8>,
+    speed_profile: Option<Vec<(f64, u8)>>,
+    temp_source: Option<TempSource>,
+}
+
+/// Mirrors the full per-channel `Setting` shape, minus `channel_name` (which lives in the outer
+/// TOML key, not inside the channel's own inline table).
+#[derive(Debug, Deserialize)]
+struct ChannelSettingFields {
+    speed_fixed: Option<u8>,
+    speed_profile: Option<Vec<(f64, u8)>>,
+    temp_source: Option<TempSource>,
+    lighting: Option<LightingSettings>,
+    lcd: Option<LcdSettings>,
+    pwm_mode: Option<u8>,
+    profile_uid: Option<String>,
+}
+
+/// A single field-level validation failure from [`Config::validate_function`], naming the
+/// offending TOML key so the caller doesn't have to guess which value was rejected.
+#[derive(Debug, Clone)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
 pub struct Config {
     path: PathBuf,
     path_ui: PathBuf,
     document: RwLock<Document>,
+
+    /// Parsed `CONFIG_D_DIR` fragments, in the lexical order they were loaded, deep-merged over
+    /// `document` by [`Config::effective_document`] for every read. Never written to - only the
+    /// base `document` is ever saved, so overlay content never leaks into the user's config.toml.
+    config_d_fragments: Vec<Document>,
+}
+
+/// An override layer for a subset of `CoolerControlSettings`, resolved as "env var if present,
+/// else whatever `Config::get_settings` read from config.toml." Populated from environment
+/// variables today; a CLI flag layer would slot in the same way once this daemon grows an
+/// argument parser, which this trimmed snapshot doesn't have (there is no `main.rs`/`clap` setup
+/// anywhere in this tree to wire flags into, so only the env var layer is implemented here).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoolerControlSettingsOverrides {
+    pub apply_on_boot: Option<bool>,
+    pub no_init: Option<bool>,
+    pub startup_delay: Option<Duration>,
+    pub smoothing_level: Option<u8>,
+    pub thinkpad_full_speed: Option<bool>,
+}
+
+impl CoolerControlSettingsOverrides {
+    /// Reads `CC_APPLY_ON_BOOT`, `CC_NO_INIT`, `CC_STARTUP_DELAY`, `CC_SMOOTHING_LEVEL`, and
+    /// `CC_THINKPAD_FULL_SPEED` from the process environment, leaving a field `None` when its
+    /// variable is unset or fails to parse.
+    pub fn from_env() -> Self {
+        Self {
+            apply_on_boot: Self::env_bool("CC_APPLY_ON_BOOT"),
+            no_init: Self::env_bool("CC_NO_INIT"),
+            startup_delay: env::var("CC_STARTUP_DELAY").ok()
+                .and_then(|value| Config::parse_duration_string(&value).ok()),
+            smoothing_level: env::var("CC_SMOOTHING_LEVEL").ok()
+                .and_then(|value| value.parse().ok()),
+            thinkpad_full_speed: Self::env_bool("CC_THINKPAD_FULL_SPEED"),
+        }
+    }
+
+    fn env_bool(name: &str) -> Option<bool> {
+        env::var(name).ok().and_then(|value| value.parse().ok())
+    }
+
+    /// Folds this override layer over `settings`, letting any `Some` field here win over the
+    /// value read from config.toml. The caller must not feed the result back into
+    /// `Config::set_settings` - doing so would persist a one-off override (e.g. a single run
+    /// with `CC_NO_INIT=true`) into the user's configuration file.
+    pub fn resolve(&self, mut settings: CoolerControlSettings) -> CoolerControlSettings {
+        if let Some(apply_on_boot) = self.apply_on_boot {
+            settings.apply_on_boot = apply_on_boot;
+        }
+        if let Some(no_init) = self.no_init {
+            settings.no_init = no_init;
+        }
+        if let Some(startup_delay) = self.startup_delay {
+            settings.startup_delay = startup_delay;
+        }
+        if let Some(smoothing_level) = self.smoothing_level {
+            settings.smoothing_level = smoothing_level;
+        }
+        if let Some(thinkpad_full_speed) = self.thinkpad_full_speed {
+            settings.thinkpad_full_speed = thinkpad_full_speed;
+        }
+        settings
+    }
 }
 
 impl Config {
@@ -53,24 +248,29 @@ impl Config {
         }
         let path = Path::new(DEFAULT_CONFIG_FILE_PATH).to_path_buf();
         let path_ui = Path::new(DEFAULT_UI_CONFIG_FILE_PATH).to_path_buf();
-        let config_contents = match tokio::fs::read_to_string(&path).await {
-            Ok(contents) => contents,
+        let mut document = match Self::read_and_parse_document(&path).await {
+            Ok(document) => document,
             Err(err) => {
-                warn!("Error trying to read configuration file: {}", err);
-                warn!("Attempting to write a new configuration file");
-                tokio::fs::write(&path, DEFAULT_CONFIG_FILE_BYTES).await
-                    .with_context(|| format!("Writing new configuration file: {:?}", path))?;
-                tokio::fs::read_to_string(&path).await
-                    .with_context(|| format!("Reading configuration file {:?}", path))?
+                warn!("Error trying to read/parse configuration file: {}", err);
+                if let Some(document) = Self::restore_most_recent_bak(&path).await {
+                    warn!("Restored configuration file from the most recent backup");
+                    document
+                } else {
+                    warn!("No usable backup found. Writing a new default configuration file");
+                    tokio::fs::write(&path, DEFAULT_CONFIG_FILE_BYTES).await
+                        .with_context(|| format!("Writing new configuration file: {:?}", path))?;
+                    Self::read_and_parse_document(&path).await?
+                }
             }
         };
-        let document = config_contents.parse::<Document>()
-            .with_context(|| "Parsing configuration file")?;
+        let migrated = migrate_document(&mut document)?;
         debug!("Loaded configuration file:\n{}", document);
+        let config_d_fragments = Self::load_config_d_fragments().await?;
         let config = Self {
             path,
             path_ui,
             document: RwLock::new(document),
+            config_d_fragments,
         };
         // test parsing of config data to make sure everything is readable
         let _ = config.legacy690_ids().await?;
@@ -92,14 +292,175 @@ impl Config {
             return Err(err);
         };
         info!("Configuration file check successful");
+        if migrated {
+            info!("Persisting migrated configuration file (now schema version {CONFIG_SCHEMA_VERSION})");
+            config.save_config_file().await?;
+        }
         Ok(config)
     }
 
+    /// Reads and parses the configuration file at `path`, with no fallback - callers decide what
+    /// to do (restore a backup, write a fresh default) if this fails.
+    async fn read_and_parse_document(path: &Path) -> Result<Document> {
+        let contents = tokio::fs::read_to_string(path).await
+            .with_context(|| format!("Reading configuration file {:?}", path))?;
+        contents.parse::<Document>().with_context(|| "Parsing configuration file")
+    }
+
+    /// Looks next to `path` for the most recent `.bak` file written by `save_config_file`, named
+    /// `<file name>.<timestamp>.CONFIG_BAK_EXTENSION` so lexical sort order is chronological, and
+    /// returns its parsed `Document`. Returns `None` if there isn't one or it also fails to parse.
+    async fn restore_most_recent_bak(path: &Path) -> Option<Document> {
+        let dir = path.parent()?;
+        let file_name = path.file_name()?.to_str()?;
+        let mut entries = tokio::fs::read_dir(dir).await.ok()?;
+        let mut bak_paths = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let entry_path = entry.path();
+            let Some(name) = entry_path.file_name().and_then(|n| n.to_str()) else { continue };
+            if name.starts_with(file_name) && name.ends_with(&format!(".{CONFIG_BAK_EXTENSION}")) {
+                bak_paths.push(entry_path);
+            }
+        }
+        bak_paths.sort();
+        let most_recent = bak_paths.pop()?;
+        let contents = tokio::fs::read_to_string(&most_recent).await.ok()?;
+        let document = contents.parse::<Document>().ok()?;
+        info!("Restoring configuration from backup: {:?}", most_recent);
+        Some(document)
+    }
+
+    /// Parses `contents` and runs `load_config_file`'s settings, device settings, profile and
+    /// function checks against a throwaway `Config` instance, so `save_config_file` can refuse to
+    /// persist a document that would fail those checks on load.
+    async fn validate_serialized_config(contents: &str) -> Result<()> {
+        let document = contents.parse::<Document>()
+            .with_context(|| "Parsing serialized configuration file")?;
+        let scratch = Self {
+            path: PathBuf::new(),
+            path_ui: PathBuf::new(),
+            document: RwLock::new(document),
+            config_d_fragments: Vec::new(),
+        };
+        let _ = scratch.get_settings().await?;
+        scratch.get_all_devices_settings().await?;
+        scratch.get_profiles().await?;
+        scratch.get_functions().await?;
+        Ok(())
+    }
+
+    /// Scans `CONFIG_D_DIR` for `*.toml` fragments in lexical filename order and parses each one.
+    /// Returns an empty `Vec` if the directory doesn't exist - the feature is entirely optional.
+    async fn load_config_d_fragments() -> Result<Vec<Document>> {
+        let config_d_dir = Path::new(CONFIG_D_DIR);
+        if !config_d_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut fragment_paths = Vec::new();
+        let mut entries = tokio::fs::read_dir(config_d_dir).await
+            .with_context(|| format!("Reading config.d directory: {:?}", config_d_dir))?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("toml") {
+                fragment_paths.push(path);
+            }
+        }
+        fragment_paths.sort();
+        let mut fragments = Vec::with_capacity(fragment_paths.len());
+        for path in fragment_paths {
+            let contents = tokio::fs::read_to_string(&path).await
+                .with_context(|| format!("Reading config.d fragment: {:?}", path))?;
+            let fragment = contents.parse::<Document>()
+                .with_context(|| format!("Parsing config.d fragment: {:?}", path))?;
+            info!("Loaded config.d fragment: {:?}", path);
+            fragments.push(fragment);
+        }
+        Ok(fragments)
+    }
+
+    /// The base `document` deep-merged with every `config_d_fragments` entry layered on top, in
+    /// load order, so a later fragment overrides an earlier one's keys. This is what every getter
+    /// reads from; only the unmerged base `document` is ever written back to disk, so writes never
+    /// duplicate overlay content into the user's config.toml.
+    async fn effective_document(&self) -> Document {
+        let mut effective = self.document.read().await.clone();
+        for fragment in &self.config_d_fragments {
+            Self::deep_merge_table(effective.as_table_mut(), fragment.as_table());
+        }
+        effective
+    }
+
+    /// Recursively merges `overlay`'s entries into `base`: a key present as a table in both is
+    /// merged key-by-key; `profiles`/`functions` arrays-of-tables are merged entry-by-entry by
+    /// matching `uid` (see [`Self::merge_array_of_tables_by_uid`]); anything else (scalars, plain
+    /// arrays) is overwritten wholesale, so the overlay always wins for the keys it actually sets.
+    fn deep_merge_table(base: &mut Table, overlay: &Table) {
+        for (key, overlay_item) in overlay.iter() {
+            if (key == "profiles" || key == "functions") && overlay_item.is_array_of_tables() {
+                let overlay_array = overlay_item.as_array_of_tables().expect("just checked");
+                let base_item = base[key].or_insert(Item::ArrayOfTables(ArrayOfTables::new()));
+                if let Some(base_array) = base_item.as_array_of_tables_mut() {
+                    Self::merge_array_of_tables_by_uid(base_array, overlay_array);
+                    continue;
+                }
+            }
+            let both_tables = overlay_item.is_table()
+                && base.get(key).map(Item::is_table).unwrap_or(false);
+            if both_tables {
+                let overlay_table = overlay_item.as_table().expect("just checked is_table");
+                let base_table = base.get_mut(key).and_then(Item::as_table_mut)
+                    .expect("just checked is_table");
+                Self::deep_merge_table(base_table, overlay_table);
+            } else {
+                base[key] = overlay_item.clone();
+            }
+        }
+    }
+
+    /// Merges `overlay` entries into `base` by matching each table's `uid` key: an overlay entry
+    /// whose `uid` matches an existing `base` entry replaces it in place, so a drop-in fragment
+    /// can override one profile/function without clobbering the rest of the array; anything
+    /// without a matching `uid` is appended.
+    fn merge_array_of_tables_by_uid(base: &mut ArrayOfTables, overlay: &ArrayOfTables) {
+        for overlay_table in overlay.iter() {
+            let overlay_uid = overlay_table.get("uid").and_then(Item::as_str);
+            let existing_index = overlay_uid.and_then(|uid| {
+                base.iter().position(|table| table.get("uid").and_then(Item::as_str) == Some(uid))
+            });
+            match existing_index {
+                Some(index) => {
+                    *base.get_mut(index).expect("index just found by position") = overlay_table.clone();
+                }
+                None => {
+                    base.push(overlay_table.clone());
+                }
+            }
+        }
+    }
+
     /// saves any changes to the configuration file - preserving formatting and comments
     pub async fn save_config_file(&self) -> Result<()> {
-        tokio::fs::write(
-            &self.path, self.document.read().await.to_string(),
-        ).await.with_context(|| format!("Saving configuration file: {:?}", &self.path))
+        let contents = self.document.read().await.to_string();
+        Self::validate_serialized_config(&contents).await
+            .with_context(|| "Refusing to save an invalid configuration file")?;
+        if let Ok(previous_contents) = tokio::fs::read_to_string(&self.path).await {
+            let bak_path = self.path.with_extension(format!(
+                "toml.{}.{CONFIG_BAK_EXTENSION}", Local::now().format("%Y%m%d%H%M%S%3f")
+            ));
+            if let Err(err) = tokio::fs::write(&bak_path, previous_contents).await {
+                warn!("Could not write configuration backup {:?}: {}", bak_path, err);
+            }
+            self.prune_old_config_baks().await;
+        }
+        let tmp_path = self.path.with_extension("toml.tmp");
+        let mut tmp_file = tokio::fs::File::create(&tmp_path).await
+            .with_context(|| format!("Creating temp configuration file: {:?}", tmp_path))?;
+        tmp_file.write_all(contents.as_bytes()).await
+            .with_context(|| format!("Writing temp configuration file: {:?}", tmp_path))?;
+        tmp_file.sync_all().await
+            .with_context(|| format!("Fsyncing temp configuration file: {:?}", tmp_path))?;
+        tokio::fs::rename(&tmp_path, &self.path).await
+            .with_context(|| format!("Renaming temp configuration file over: {:?}", &self.path))
     }
 
     pub async fn save_ui_config_file(&self, ui_settings: &String) -> Result<()> {
@@ -113,6 +474,143 @@ impl Config {
             .await.with_context(|| format!("Loading UI configuration file {:?}", &self.path_ui))
     }
 
+    /// Lists all stored configuration backups, most recent first.
+    pub async fn list_backups(&self) -> Result<Vec<ConfigBackupMetadata>> {
+        let backup_dir = self.backup_dir();
+        if !backup_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut entries = tokio::fs::read_dir(&backup_dir).await
+            .with_context(|| format!("Reading backup directory: {:?}", backup_dir))?;
+        let mut backups = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let metadata_path = entry.path().join(BACKUP_METADATA_FILE_NAME);
+            let Ok(metadata_contents) = tokio::fs::read_to_string(&metadata_path).await else {
+                continue;
+            };
+            let Ok(metadata) = serde_json::from_str::<ConfigBackupMetadata>(&metadata_contents) else {
+                continue;
+            };
+            backups.push(metadata);
+        }
+        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(backups)
+    }
+
+    /// Captures the current daemon config and UI config as a named, timestamped snapshot,
+    /// pruning the oldest backup beyond `MAX_RETAINED_BACKUPS` if necessary.
+    pub async fn create_backup(&self, name: String) -> Result<ConfigBackupMetadata> {
+        let metadata = ConfigBackupMetadata {
+            id: Uuid::new_v4().to_string(),
+            name,
+            created_at: Local::now(),
+            schema_version: CONFIG_SCHEMA_VERSION,
+        };
+        let backup_dir = self.backup_dir().join(&metadata.id);
+        tokio::fs::create_dir_all(&backup_dir).await
+            .with_context(|| format!("Creating backup directory: {:?}", backup_dir))?;
+        tokio::fs::write(
+            backup_dir.join(BACKUP_CONFIG_FILE_NAME),
+            self.document.read().await.to_string(),
+        ).await.with_context(|| "Writing backup config.toml")?;
+        if let Ok(ui_config) = self.load_ui_config_file().await {
+            tokio::fs::write(backup_dir.join(BACKUP_UI_CONFIG_FILE_NAME), ui_config)
+                .await.with_context(|| "Writing backup config-ui.json")?;
+        }
+        tokio::fs::write(
+            backup_dir.join(BACKUP_METADATA_FILE_NAME),
+            serde_json::to_string(&metadata)?,
+        ).await.with_context(|| "Writing backup metadata.json")?;
+        self.prune_old_backups().await?;
+        Ok(metadata)
+    }
+
+    /// Atomically swaps the live config to the given backup and reloads it into memory. The
+    /// backup's schema version must match the current `CONFIG_SCHEMA_VERSION`, otherwise a
+    /// `CCError::UserError` is returned rather than risking a corrupted restore.
+    pub async fn restore_backup(&self, id: &str) -> Result<()> {
+        let backup_dir = self.backup_dir().join(id);
+        let metadata_contents = tokio::fs::read_to_string(backup_dir.join(BACKUP_METADATA_FILE_NAME))
+            .await
+            .with_context(|| format!("Backup not found: {id}"))?;
+        let metadata: ConfigBackupMetadata = serde_json::from_str(&metadata_contents)
+            .with_context(|| "Parsing backup metadata.json")?;
+        if metadata.schema_version != CONFIG_SCHEMA_VERSION {
+            return Err(CCError::UserError {
+                msg: format!(
+                    "Backup schema version {} is incompatible with the current schema version {CONFIG_SCHEMA_VERSION}",
+                    metadata.schema_version
+                ),
+            }.into());
+        }
+        let backup_config_contents = tokio::fs::read_to_string(backup_dir.join(BACKUP_CONFIG_FILE_NAME))
+            .await.with_context(|| "Reading backup config.toml")?;
+        let restored_document = backup_config_contents.parse::<Document>()
+            .with_context(|| "Parsing backup config.toml")?;
+        // write to a temp file and rename it over the config file first, so a crash mid-restore
+        // still leaves a valid config file on disk, then swap the in-memory document to match.
+        let tmp_path = self.path.with_extension("toml.tmp");
+        tokio::fs::write(&tmp_path, backup_config_contents).await
+            .with_context(|| format!("Writing temp configuration file: {:?}", tmp_path))?;
+        tokio::fs::rename(&tmp_path, &self.path).await
+            .with_context(|| format!("Restoring configuration file: {:?}", &self.path))?;
+        *self.document.write().await = restored_document;
+        let backup_ui_path = backup_dir.join(BACKUP_UI_CONFIG_FILE_NAME);
+        if let Ok(backup_ui_contents) = tokio::fs::read_to_string(&backup_ui_path).await {
+            tokio::fs::write(&self.path_ui, backup_ui_contents).await
+                .with_context(|| format!("Restoring UI configuration file: {:?}", &self.path_ui))?;
+        }
+        Ok(())
+    }
+
+    fn backup_dir(&self) -> PathBuf {
+        Path::new(DEFAULT_CONFIG_DIR).join(BACKUP_DIR_NAME)
+    }
+
+    /// Keeps only the `MAX_RETAINED_CONFIG_BAKS` most recent `config.toml.<ts>.bak` sibling files
+    /// `save_config_file` writes on every save, deleting the rest. Mirrors `prune_old_backups`
+    /// below for the separate, user-facing `backups/` snapshot feature.
+    async fn prune_old_config_baks(&self) {
+        let Some(dir) = self.path.parent() else { return };
+        let Some(file_name) = self.path.file_name().and_then(|n| n.to_str()) else { return };
+        let Ok(mut entries) = tokio::fs::read_dir(dir).await else { return };
+        let mut bak_paths = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let entry_path = entry.path();
+            let Some(name) = entry_path.file_name().and_then(|n| n.to_str()) else { continue };
+            if name.starts_with(file_name) && name.ends_with(&format!(".{CONFIG_BAK_EXTENSION}")) {
+                bak_paths.push(entry_path);
+            }
+        }
+        if bak_paths.len() <= MAX_RETAINED_CONFIG_BAKS {
+            return;
+        }
+        // Lexical sort order is chronological (see the naming note on CONFIG_BAK_EXTENSION),
+        // so the oldest files are at the front once sorted ascending.
+        bak_paths.sort();
+        for stale_bak in bak_paths.into_iter().rev().skip(MAX_RETAINED_CONFIG_BAKS) {
+            if let Err(err) = tokio::fs::remove_file(&stale_bak).await {
+                warn!("Failed to prune old configuration backup {:?}: {err}", stale_bak);
+            }
+        }
+    }
+
+    /// Keeps only the `MAX_RETAINED_BACKUPS` most recent backups, deleting the rest.
+    async fn prune_old_backups(&self) -> Result<()> {
+        let mut backups = self.list_backups().await?;
+        if backups.len() <= MAX_RETAINED_BACKUPS {
+            return Ok(());
+        }
+        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        for stale_backup in backups.into_iter().skip(MAX_RETAINED_BACKUPS) {
+            let stale_dir = self.backup_dir().join(&stale_backup.id);
+            if let Err(err) = tokio::fs::remove_dir_all(&stale_dir).await {
+                warn!("Failed to prune old config backup {:?}: {err}", stale_dir);
+            }
+        }
+        Ok(())
+    }
+
     /// This adds a human readable device list with UIDs to the config file
     pub async fn create_device_list(&self, devices: Arc<HashMap<UID, DeviceLock>>) -> Result<()> {
         for (uid, device) in devices.iter() {
@@ -125,7 +623,7 @@ impl Config {
 
     pub async fn legacy690_ids(&self) -> Result<HashMap<String, bool>> {
         let mut legacy690_ids = HashMap::new();
-        if let Some(table) = self.document.read().await["legacy690"].as_table() {
+        if let Some(table) = self.effective_document().await["legacy690"].as_table() {
             for (key, value) in table.iter() {
                 legacy690_ids.insert(
                     key.to_string(),
@@ -219,8 +717,13 @@ impl Config {
                 Value::Boolean(Formatted::new(true))
             );
         }
+        let colors = if let Some(kelvin) = lighting.temperature_k {
+            vec![kelvin_to_rgb(kelvin)]
+        } else {
+            lighting.colors.clone()
+        };
         let mut color_array = toml_edit::Array::new();
-        for (r, g, b) in lighting.colors.clone() {
+        for (r, g, b) in colors {
             let mut rgb_array = toml_edit::Array::new();
             rgb_array.push(Value::Integer(Formatted::new(r as i64)));
             rgb_array.push(Value::Integer(Formatted::new(g as i64)));
@@ -230,6 +733,11 @@ impl Config {
         channel_setting["lighting"]["colors"] = Item::Value(
             Value::Array(color_array)
         );
+        if let Some(kelvin) = lighting.temperature_k {
+            channel_setting["lighting"]["temperature_k"] = Item::Value(
+                Value::Integer(Formatted::new(i64::from(kelvin)))
+            );
+        }
     }
 
     fn set_setting_lcd(channel_setting: &mut Item, setting: &Setting, lcd: &LcdSettings) {
@@ -309,29 +817,24 @@ impl Config {
     /// This has to be done defensively, as the user may change the config file.
     pub async fn get_device_settings(&self, device_uid: &str) -> Result<Vec<Setting>> {
         let mut settings = Vec::new();
-        if let Some(table_item) = self.document.read().await["device-settings"].get(device_uid) {
+        if let Some(table_item) = self.effective_document().await["device-settings"].get(device_uid) {
             let table = table_item.as_table().with_context(|| "device setting should be a table")?;
             for (channel_name, base_item) in table.iter() {
                 let setting_table = base_item.as_inline_table()
                     .with_context(|| "Channel Setting should be an inline table")?
                     .clone().into_table();
-                let speed_fixed = Self::get_speed_fixed(&setting_table)?;
-                let speed_profile = Self::get_speed_profile(&setting_table)?;
-                let temp_source = Self::get_temp_source(&setting_table)?;
-                let lighting = Self::get_lighting(&setting_table)?;
-                let lcd = Self::get_lcd(&setting_table)?;
-                let pwm_mode = Self::get_pwm_mode(&setting_table)?;
-                let profile_uid = Self::get_profile_uid(&setting_table)?;
+                let fields: ChannelSettingFields = toml_edit::de::from_str(&setting_table.to_string())
+                    .with_context(|| format!("Parsing settings for channel '{channel_name}'"))?;
                 settings.push(Setting {
                     channel_name: channel_name.to_string(),
-                    speed_fixed,
-                    speed_profile,
-                    temp_source,
-                    lighting,
-                    lcd,
-                    pwm_mode,
+                    speed_fixed: fields.speed_fixed,
+                    speed_profile: fields.speed_profile,
+                    temp_source: fields.temp_source,
+                    lighting: fields.lighting,
+                    lcd: fields.lcd,
+                    pwm_mode: fields.pwm_mode,
                     reset_to_default: None,
-                    profile_uid,
+                    profile_uid: fields.profile_uid,
                 });
             }
         }
@@ -340,7 +843,7 @@ impl Config {
 
     async fn get_all_devices_settings(&self) -> Result<HashMap<UID, Vec<Setting>>> {
         let mut devices_settings = HashMap::new();
-        if let Some(device_table) = self.document.read().await["device-settings"].as_table() {
+        if let Some(device_table) = self.effective_document().await["device-settings"].as_table() {
             for (device_uid, _value) in device_table {
                 let settings = self.get_device_settings(device_uid).await?;
                 devices_settings.insert(device_uid.to_string(), settings);
@@ -351,7 +854,7 @@ impl Config {
 
     async fn get_all_cc_devices_settings(&self) -> Result<HashMap<UID, Option<CoolerControlDeviceSettings>>> {
         let mut devices_settings = HashMap::new();
-        if let Some(device_table) = self.document.read().await["settings"].as_table() {
+        if let Some(device_table) = self.effective_document().await["settings"].as_table() {
             for (device_uid, _value) in device_table {
                 if device_uid.len() == 64 { // there are other settings here, we want only the ones with proper UIDs
                     let settings = self.get_cc_settings_for_device(device_uid).await?;
@@ -362,209 +865,9 @@ impl Config {
         Ok(devices_settings)
     }
 
-    fn get_speed_fixed(setting_table: &Table) -> Result<Option<u8>> {
-        let speed_fixed = if let Some(speed_value) = setting_table.get("speed_fixed") {
-            let speed: u8 = speed_value
-                .as_integer().with_context(|| "speed_fixed should be an integer")?
-                .try_into().ok().with_context(|| "speed_fixed must be a value between 0-100")?;
-            Some(speed)
-        } else { None };
-        Ok(speed_fixed)
-    }
-
-    fn get_speed_profile(setting_table: &Table) -> Result<Option<Vec<(f64, u8)>>> {
-        let speed_profile = if let Some(value) = setting_table.get("speed_profile") {
-            let mut profiles = Vec::new();
-            let speeds = value.as_array().with_context(|| "profile should be an array")?;
-            for profile_pair_value in speeds.iter() {
-                let profile_pair_array = profile_pair_value.as_array()
-                    .with_context(|| "profile pairs should be an array")?;
-                let temp_value = profile_pair_array.get(0)
-                    .with_context(|| "Speed Profiles must be pairs")?;
-                // toml edit can't convert 20 to a float like 20.0. We need to handle integer values:
-                let temp: f64 = match temp_value.as_float() {
-                    None => {
-                        let temp_i64 = temp_value
-                            .as_integer().with_context(|| "Speed Profile Temps must be integers or floats")?;
-                        if temp_i64 > f64::MAX as i64 {
-                            f64::MAX
-                        } else if temp_i64 < f64::MIN as i64 {
-                            f64::MIN
-                        } else {
-                            temp_i64 as f64
-                        }
-                    }
-                    Some(temp_f64) => temp_f64
-                };
-                let speed: u8 = profile_pair_array.get(1)
-                    .with_context(|| "Speed Profiles must be pairs")?
-                    .as_integer().with_context(|| "Speed Profile Duties must be integers")?
-                    .try_into().ok().with_context(|| "speed profiles must be values between 0-100")?;
-                profiles.push((temp, speed));
-            }
-            Some(profiles)
-        } else { None };
-        Ok(speed_profile)
-    }
-
-    fn get_temp_source(setting_table: &Table) -> Result<Option<TempSource>> {
-        let temp_source = if let Some(value) = setting_table.get("temp_source") {
-            let temp_source_table = value.as_inline_table()
-                .with_context(|| "temp_source should be an inline table")?;
-            let temp_name = temp_source_table.get("temp_name")
-                .with_context(|| "temp_source must have temp_name and device_uid set")?
-                .as_str().with_context(|| "temp_name should be a String")?
-                .to_string();
-            let device_uid = temp_source_table.get("device_uid")
-                .with_context(|| "temp_source must have frontend_temp_name and device_uid set")?
-                .as_str().with_context(|| "device_uid should be a String")?
-                .to_string();
-            Some(TempSource {
-                temp_name,
-                device_uid,
-            })
-        } else { None };
-        Ok(temp_source)
-    }
-
-    fn get_lighting(setting_table: &Table) -> Result<Option<LightingSettings>> {
-        let lighting = if let Some(value) = setting_table.get("lighting") {
-            let lighting_table = value.as_inline_table()
-                .with_context(|| "lighting should be an inline table")?;
-            let mode = lighting_table.get("mode")
-                .with_context(|| "lighting.mode should be present")?
-                .as_str().with_context(|| "lighting.mode should be a String")?
-                .to_string();
-            let speed = if let Some(value) = lighting_table.get("speed") {
-                Some(value
-                    .as_str().with_context(|| "lighting.speed should be a String")?
-                    .to_string()
-                )
-            } else { None };
-            let backward = if let Some(value) = setting_table.get("backward") {
-                Some(value.as_bool().with_context(|| "lighting.backward should be a boolean")?)
-            } else { None };
-            let mut colors = Vec::new();
-            let colors_array = lighting_table.get("colors")
-                .with_context(|| "lighting.colors should always be present")?
-                .as_array().with_context(|| "lighting.colors should be an array")?;
-            for rgb_value in colors_array {
-                let rgb_array = rgb_value.as_array()
-                    .with_context(|| "RGB values should be an array")?;
-                let r: u8 = rgb_array.get(0)
-                    .with_context(|| "RGB values must be in arrays of 3")?
-                    .as_integer().with_context(|| "RGB values must be integers")?
-                    .try_into().ok().with_context(|| "RGB values must be between 0-255")?;
-                let g: u8 = rgb_array.get(1)
-                    .with_context(|| "RGB values must be in arrays of 3")?
-                    .as_integer().with_context(|| "RGB values must be integers")?
-                    .try_into().ok().with_context(|| "RGB values must be between 0-255")?;
-                let b: u8 = rgb_array.get(2)
-                    .with_context(|| "RGB values must be in arrays of 3")?
-                    .as_integer().with_context(|| "RGB values must be integers")?
-                    .try_into().ok().with_context(|| "RGB values must be between 0-255")?;
-                colors.push((r, g, b))
-            }
-            Some(LightingSettings {
-                mode,
-                speed,
-                backward,
-                colors,
-            })
-        } else { None };
-        Ok(lighting)
-    }
-
-    fn get_lcd(setting_table: &Table) -> Result<Option<LcdSettings>> {
-        let lcd = if let Some(value) = setting_table.get("lcd") {
-            let lcd_table = value.as_inline_table()
-                .with_context(|| "lcd should be an inline table")?;
-            let mode = lcd_table.get("mode")
-                .with_context(|| "lcd.mode should be present")?
-                .as_str().with_context(|| "lcd.mode should be a String")?
-                .to_string();
-            let brightness = if let Some(brightness_value) = lcd_table.get("brightness") {
-                let brightness_u8: u8 = brightness_value.as_integer()
-                    .with_context(|| "brightness should be an integer")?
-                    .try_into().ok().with_context(|| "brightness should be a value between 0-100")?;
-                Some(brightness_u8)
-            } else { None };
-            let orientation = if let Some(orientation_value) = lcd_table.get("orientation") {
-                let orientation_u16: u16 = orientation_value.as_integer()
-                    .with_context(|| "orientation should be an integer")?
-                    .try_into().ok().with_context(|| "orientation should be a value between 0-270")?;
-                Some(orientation_u16)
-            } else { None };
-            let image_file_src = if let Some(image_file_src_value) = lcd_table.get("image_file_src") {
-                Some(image_file_src_value
-                    .as_str().with_context(|| "image_file_src should be a String")?
-                    .to_string()
-                )
-            } else { None };
-            let image_file_processed = if let Some(image_file_processed_value) = lcd_table.get("image_file_processed") {
-                Some(image_file_processed_value
-                    .as_str().with_context(|| "image_file_processed should be a String")?
-                    .to_string()
-                )
-            } else { None };
-            let mut colors = Vec::new();
-            let colors_array = lcd_table.get("colors")
-                .with_context(|| "lcd.colors should always be present")?
-                .as_array().with_context(|| "lcd.colors should be an array")?;
-            for rgb_value in colors_array {
-                let rgb_array = rgb_value.as_array()
-                    .with_context(|| "RGB values should be an array")?;
-                let r: u8 = rgb_array.get(0)
-                    .with_context(|| "RGB values must be in arrays of 3")?
-                    .as_integer().with_context(|| "RGB values must be integers")?
-                    .try_into().ok().with_context(|| "RGB values must be between 0-255")?;
-                let g: u8 = rgb_array.get(1)
-                    .with_context(|| "RGB values must be in arrays of 3")?
-                    .as_integer().with_context(|| "RGB values must be integers")?
-                    .try_into().ok().with_context(|| "RGB values must be between 0-255")?;
-                let b: u8 = rgb_array.get(2)
-                    .with_context(|| "RGB values must be in arrays of 3")?
-                    .as_integer().with_context(|| "RGB values must be integers")?
-                    .try_into().ok().with_context(|| "RGB values must be between 0-255")?;
-                colors.push((r, g, b))
-            }
-            let temp_source = Self::get_temp_source(&lcd_table.clone().into_table())?;
-            Some(LcdSettings {
-                mode,
-                brightness,
-                orientation,
-                image_file_src,
-                image_file_processed,
-                colors,
-                temp_source,
-            })
-        } else { None };
-        Ok(lcd)
-    }
-
-    fn get_pwm_mode(setting_table: &Table) -> Result<Option<u8>> {
-        let pwm_mode = if let Some(value) = setting_table.get("pwm_mode") {
-            let p_mode: u8 = value
-                .as_integer().with_context(|| "pwm_mode should be an integer")?
-                .try_into().ok().with_context(|| "pwm_mode should be a value between 0-2")?;
-            Some(p_mode)
-        } else { None };
-        Ok(pwm_mode)
-    }
-
-    fn get_profile_uid(setting_table: &Table) -> Result<Option<String>> {
-        let profile_uid = if let Some(value) = setting_table.get("profile_uid") {
-            let p_uid = value
-                .as_str().with_context(|| "profile_uid should be a String")?
-                .to_string();
-            Some(p_uid)
-        } else { None };
-        Ok(profile_uid)
-    }
-
     /// Returns CoolerControl general settings
     pub async fn get_settings(&self) -> Result<CoolerControlSettings> {
-        if let Some(settings_item) = self.document.read().await.get("settings") {
+        if let Some(settings_item) = self.effective_document().await.get("settings") {
             let settings = settings_item.as_table().with_context(|| "Settings should be a table")?;
             let apply_on_boot = settings.get("apply_on_boot")
                 .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(true))))
@@ -575,13 +878,10 @@ impl Config {
             let handle_dynamic_temps = settings.get("handle_dynamic_temps")
                 .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(false))))
                 .as_bool().with_context(|| "handle_dynamic_temps should be a boolean value")?;
-            let startup_delay = Duration::from_secs(
-                settings.get("startup_delay")
-                    .unwrap_or(&Item::Value(Value::Integer(Formatted::new(2))))
-                    .as_integer().with_context(|| "startup_delay should be an integer value")?
-                    .max(0)
-                    .min(10) as u64
-            );
+            let startup_delay = Self::parse_duration_item(
+                settings.get("startup_delay").unwrap_or(&Item::Value(Value::Integer(Formatted::new(2)))),
+                "startup_delay",
+            )?.clamp(Duration::ZERO, Duration::from_secs(10));
             let smoothing_level = settings.get("smoothing_level")
                 .unwrap_or(&Item::Value(Value::Integer(Formatted::new(0))))
                 .as_integer().with_context(|| "smoothing_level should be an integer value")?
@@ -590,6 +890,20 @@ impl Config {
             let thinkpad_full_speed = settings.get("thinkpad_full_speed")
                 .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(false))))
                 .as_bool().with_context(|| "thinkpad_full_speed should be a boolean value")?;
+            let power_aware_polling = settings.get("power_aware_polling")
+                .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(true))))
+                .as_bool().with_context(|| "power_aware_polling should be a boolean value")?;
+            let temp_unit_str = settings.get("temp_unit")
+                .unwrap_or(&Item::Value(Value::String(Formatted::new("Celsius".to_string()))))
+                .as_str().with_context(|| "temp_unit should be a String")?;
+            let temp_unit = TempUnit::from_str(temp_unit_str)
+                .with_context(|| format!("Unknown temp_unit: {temp_unit_str}"))?;
+            let tick_rate_ms = settings.get("tick_rate_ms")
+                .unwrap_or(&Item::Value(Value::Integer(Formatted::new(1000))))
+                .as_integer().with_context(|| "tick_rate_ms should be an integer value")?
+                .max(100) as u64;
+            let auth = Self::get_auth(settings)?;
+            let temp_filter = Self::get_filter(settings, "temp_filter")?;
             Ok(CoolerControlSettings {
                 apply_on_boot,
                 no_init,
@@ -597,12 +911,103 @@ impl Config {
                 startup_delay,
                 smoothing_level,
                 thinkpad_full_speed,
+                power_aware_polling,
+                auth,
+                temp_unit,
+                tick_rate_ms,
+                temp_filter,
             })
         } else {
             Err(anyhow!("Setting table not found in configuration file"))
         }
     }
 
+    /// Reads `CoolerControlSettings` from the configuration file, then folds
+    /// `CoolerControlSettingsOverrides::from_env()` over the result, so an env var such as
+    /// `CC_NO_INIT=true` wins for the life of this process without ever being written to disk -
+    /// only [`Self::get_settings`]'s return value is persistable via [`Self::set_settings`].
+    pub async fn get_settings_with_overrides(&self) -> Result<CoolerControlSettings> {
+        let settings = self.get_settings().await?;
+        Ok(CoolerControlSettingsOverrides::from_env().resolve(settings))
+    }
+
+    /// Reads a duration field that may be stored as a bare integer (seconds, for backwards
+    /// compatibility) or a string with a trailing unit suffix (`ms`, `s`, `m`), e.g.
+    /// `startup_delay = "2500ms"`.
+    fn parse_duration_item(item: &Item, field_name: &str) -> Result<Duration> {
+        if let Some(seconds) = item.as_integer() {
+            return Ok(Duration::from_secs(seconds.max(0) as u64));
+        }
+        if let Some(value) = item.as_str() {
+            return Self::parse_duration_string(value)
+                .with_context(|| format!("{field_name} should be an integer or a duration string like \"2500ms\""));
+        }
+        Err(anyhow!("{field_name} should be an integer (seconds) or a duration string like \"2500ms\""))
+    }
+
+    /// Splits the trailing unit suffix (`ms`, `s`, `m`; defaulting to `s` when absent) from the
+    /// numeric prefix of `value` and builds the equivalent `Duration`.
+    fn parse_duration_string(value: &str) -> Result<Duration> {
+        let trimmed = value.trim();
+        let (number_str, millis_per_unit) = if let Some(prefix) = trimmed.strip_suffix("ms") {
+            (prefix, 1.0)
+        } else if let Some(prefix) = trimmed.strip_suffix('s') {
+            (prefix, 1_000.0)
+        } else if let Some(prefix) = trimmed.strip_suffix('m') {
+            (prefix, 60_000.0)
+        } else {
+            (trimmed, 1_000.0)
+        };
+        let number: f64 = number_str.trim().parse()
+            .with_context(|| format!("Invalid duration value: {value}"))?;
+        Ok(Duration::from_millis((number.max(0.0) * millis_per_unit) as u64))
+    }
+
+    /// Writes `duration` back using whichever representation (bare integer seconds, or a
+    /// duration string) was most recently supplied for `field_name`, so a user-written
+    /// `"2500ms"` isn't silently rewritten to `3` on the next save.
+    fn set_duration_item(parent: &mut Item, field_name: &str, duration: Duration) {
+        let previous_was_string = parent.get(field_name).and_then(Item::as_str).is_some();
+        parent[field_name] = if previous_was_string {
+            Item::Value(Value::String(Formatted::new(format!("{}ms", duration.as_millis()))))
+        } else {
+            Item::Value(Value::Integer(Formatted::new(duration.as_secs() as i64)))
+        };
+    }
+
+    /// Reads the `auth` sub-table, defaulting to `Auth::None` when absent so that upgrading
+    /// from a configuration file written before authentication support doesn't lock anyone out.
+    fn get_auth(settings: &Table) -> Result<Auth> {
+        let Some(auth_table) = settings.get("auth").and_then(Item::as_table) else {
+            return Ok(Auth::None);
+        };
+        let mode = auth_table.get("mode")
+            .with_context(|| "auth.mode should be present")?
+            .as_str().with_context(|| "auth.mode should be a String")?;
+        match mode {
+            "None" => Ok(Auth::None),
+            "Credentials" => {
+                let user = auth_table.get("user")
+                    .with_context(|| "auth.user should be present for Credentials mode")?
+                    .as_str().with_context(|| "auth.user should be a String")?
+                    .to_string();
+                let secret_hash = auth_table.get("secret_hash")
+                    .with_context(|| "auth.secret_hash should be present for Credentials mode")?
+                    .as_str().with_context(|| "auth.secret_hash should be a String")?
+                    .to_string();
+                Ok(Auth::Credentials { user, secret_hash })
+            }
+            "Token" => {
+                let secret_hash = auth_table.get("secret_hash")
+                    .with_context(|| "auth.secret_hash should be present for Token mode")?
+                    .as_str().with_context(|| "auth.secret_hash should be a String")?
+                    .to_string();
+                Ok(Auth::Token { secret_hash })
+            }
+            other => Err(anyhow!("Unknown auth mode: {other}")),
+        }
+    }
+
     /// Sets CoolerControl settings
     pub async fn set_settings(&self, cc_settings: &CoolerControlSettings) {
         let mut doc = self.document.write().await;
@@ -616,15 +1021,54 @@ impl Config {
         base_settings["handle_dynamic_temps"] = Item::Value(
             Value::Boolean(Formatted::new(cc_settings.handle_dynamic_temps))
         );
-        base_settings["startup_delay"] = Item::Value(
-            Value::Integer(Formatted::new(cc_settings.startup_delay.as_secs() as i64))
-        );
+        Self::set_duration_item(base_settings, "startup_delay", cc_settings.startup_delay);
         base_settings["smoothing_level"] = Item::Value(
             Value::Integer(Formatted::new(cc_settings.smoothing_level as i64))
         );
         base_settings["thinkpad_full_speed"] = Item::Value(
             Value::Boolean(Formatted::new(cc_settings.thinkpad_full_speed))
         );
+        base_settings["power_aware_polling"] = Item::Value(
+            Value::Boolean(Formatted::new(cc_settings.power_aware_polling))
+        );
+        base_settings["temp_unit"] = Item::Value(
+            Value::String(Formatted::new(cc_settings.temp_unit.to_string()))
+        );
+        base_settings["tick_rate_ms"] = Item::Value(
+            Value::Integer(Formatted::new(cc_settings.tick_rate_ms as i64))
+        );
+        Self::set_auth(base_settings, &cc_settings.auth);
+        Self::set_filter(base_settings, "temp_filter", &cc_settings.temp_filter);
+    }
+
+    fn set_auth(base_settings: &mut Item, auth: &Auth) {
+        base_settings["auth"] = Item::None; // clear previous mode's fields
+        match auth {
+            Auth::None => {
+                base_settings["auth"]["mode"] = Item::Value(
+                    Value::String(Formatted::new("None".to_string()))
+                );
+            }
+            Auth::Credentials { user, secret_hash } => {
+                base_settings["auth"]["mode"] = Item::Value(
+                    Value::String(Formatted::new("Credentials".to_string()))
+                );
+                base_settings["auth"]["user"] = Item::Value(
+                    Value::String(Formatted::new(user.clone()))
+                );
+                base_settings["auth"]["secret_hash"] = Item::Value(
+                    Value::String(Formatted::new(secret_hash.clone()))
+                );
+            }
+            Auth::Token { secret_hash } => {
+                base_settings["auth"]["mode"] = Item::Value(
+                    Value::String(Formatted::new("Token".to_string()))
+                );
+                base_settings["auth"]["secret_hash"] = Item::Value(
+                    Value::String(Formatted::new(secret_hash.clone()))
+                );
+            }
+        }
     }
 
     /// This gets the CoolerControl settings for specific devices
@@ -633,14 +1077,16 @@ impl Config {
     pub async fn get_cc_settings_for_device(
         &self, device_uid: &str,
     ) -> Result<Option<CoolerControlDeviceSettings>> {
-        if let Some(table_item) = self.document.read().await["settings"].get(device_uid) {
+        if let Some(table_item) = self.effective_document().await["settings"].get(device_uid) {
             let device_settings_table = table_item.as_table()
                 .with_context(|| "CoolerControl device settings should be a table")?;
             let disable = device_settings_table.get("disable")
                 .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(false))))
                 .as_bool().with_context(|| "disable should be a boolean value")?;
+            let temp_filter = Self::get_filter(device_settings_table, "temp_filter")?;
             Ok(Some(CoolerControlDeviceSettings {
-                disable
+                disable,
+                temp_filter,
             }))
         } else {
             Ok(None)
@@ -660,6 +1106,56 @@ impl Config {
         device_settings_table["disable"] = Item::Value(
             Value::Boolean(Formatted::new(cc_device_settings.disable))
         );
+        Self::set_filter(device_settings_table, "temp_filter", &cc_device_settings.temp_filter);
+    }
+
+    /// Reads an optional `[<key>]` subtable (e.g. `temp_filter`) into a `Filter`, defaulting
+    /// absent fields the same way `Filter::default()` would.
+    fn get_filter(table: &Table, key: &str) -> Result<Option<Filter>> {
+        let Some(filter_table) = table.get(key).and_then(Item::as_table) else {
+            return Ok(None);
+        };
+        let is_list_ignored = filter_table.get("is_list_ignored")
+            .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(false))))
+            .as_bool().with_context(|| format!("{key}.is_list_ignored should be a boolean value"))?;
+        let list = filter_table.get("list")
+            .and_then(Item::as_array)
+            .map(|array| {
+                array.iter().filter_map(Value::as_str).map(ToString::to_string).collect()
+            })
+            .unwrap_or_default();
+        let regex = filter_table.get("regex")
+            .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(false))))
+            .as_bool().with_context(|| format!("{key}.regex should be a boolean value"))?;
+        let case_sensitive = filter_table.get("case_sensitive")
+            .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(false))))
+            .as_bool().with_context(|| format!("{key}.case_sensitive should be a boolean value"))?;
+        let whole_word = filter_table.get("whole_word")
+            .unwrap_or(&Item::Value(Value::Boolean(Formatted::new(false))))
+            .as_bool().with_context(|| format!("{key}.whole_word should be a boolean value"))?;
+        Ok(Some(Filter { is_list_ignored, list, regex, case_sensitive, whole_word }))
+    }
+
+    /// Writes a `Filter` into `parent[key]`, or clears that subtable entirely when `filter` is
+    /// `None`.
+    fn set_filter(parent: &mut Item, key: &str, filter: &Option<Filter>) {
+        let Some(filter) = filter else {
+            parent[key] = Item::None;
+            return;
+        };
+        parent[key]["is_list_ignored"] = Item::Value(
+            Value::Boolean(Formatted::new(filter.is_list_ignored))
+        );
+        let mut list_array = toml_edit::Array::new();
+        for entry in &filter.list {
+            list_array.push(entry.clone());
+        }
+        parent[key]["list"] = Item::Value(Value::Array(list_array));
+        parent[key]["regex"] = Item::Value(Value::Boolean(Formatted::new(filter.regex)));
+        parent[key]["case_sensitive"] = Item::Value(
+            Value::Boolean(Formatted::new(filter.case_sensitive))
+        );
+        parent[key]["whole_word"] = Item::Value(Value::Boolean(Formatted::new(filter.whole_word)));
     }
 
     /// ////////////////////////////////////////////////////////////////////////////////////////////
@@ -670,7 +1166,7 @@ impl Config {
     /// which should always be present.
     pub async fn get_profiles(&self) -> Result<Vec<Profile>> {
         let mut profiles = Vec::new();
-        if let Some(profiles_item) = self.document.read().await.get("profiles") {
+        if let Some(profiles_item) = self.effective_document().await.get("profiles") {
             let profiles_array = profiles_item.as_array_of_tables()
                 .with_context(|| "Profiles should be an array of tables")?;
             for profile_table in profiles_array.iter() {
@@ -687,9 +1183,8 @@ impl Config {
                     .as_str().with_context(|| "Profile type should be a string")?;
                 let p_type = ProfileType::from_str(p_type_str)
                     .with_context(|| "Profile type should be a valid member")?;
-                let speed_fixed = Self::get_speed_fixed(profile_table)?;
-                let speed_profile = Self::get_speed_profile(profile_table)?;
-                let temp_source = Self::get_temp_source(profile_table)?;
+                let speed_and_temp: SpeedAndTempFields = toml_edit::de::from_str(&profile_table.to_string())
+                    .with_context(|| format!("Parsing speed/temp fields for profile '{uid}'"))?;
                 let temp_function_default_uid_value = Item::Value(Value::String(Formatted::new("0".to_string())));
                 let function_uid = profile_table.get("function_uid")
                     .unwrap_or(&temp_function_default_uid_value)
@@ -699,9 +1194,9 @@ impl Config {
                     uid,
                     p_type,
                     name,
-                    speed_fixed,
-                    speed_profile,
-                    temp_source,
+                    speed_fixed: speed_and_temp.speed_fixed,
+                    speed_profile: speed_and_temp.speed_profile,
+                    temp_source: speed_and_temp.temp_source,
                     function_uid,
                 };
                 profiles.push(profile);
@@ -845,9 +1340,13 @@ impl Config {
     /// which should be always present.
     pub async fn get_functions(&self) -> Result<Vec<Function>> {
         let mut functions = Vec::new();
-        if let Some(functions_item) = self.document.read().await.get("functions") {
-            let functions_array = functions_item.as_array_of_tables()
+        let mut document = self.effective_document().await;
+        if let Some(functions_item) = document.get_mut("functions") {
+            let functions_array = functions_item.as_array_of_tables_mut()
                 .with_context(|| "Functions should be an array of tables")?;
+            for function_table in functions_array.iter_mut() {
+                Self::migrate_function_table(function_table)?;
+            }
             for function_table in functions_array.iter() {
                 let uid = function_table.get("uid")
                     .with_context(|| "Function UID should be present")?
@@ -882,13 +1381,15 @@ impl Config {
                     Some(s_window)
                 } else { None };
                 let function = Function {
-                    uid,
+                    uid: uid.clone(),
                     name,
                     f_type,
                     response_delay,
                     deviance,
                     sample_window,
                 };
+                let function = Self::validate_function(function)
+                    .map_err(|errors| Self::function_validation_error(&uid, &errors))?;
                 functions.push(function);
             }
         } else {
@@ -925,6 +1426,9 @@ impl Config {
 
     /// Sets the given new Function
     pub async fn set_function(&self, function: Function) -> Result<()> {
+        let uid = function.uid.clone();
+        let function = Self::validate_function(function)
+            .map_err(|errors| Self::function_validation_error(&uid, &errors))?;
         let mut doc = self.document.write().await;
         let functions_array = doc["functions"]
             .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
@@ -941,6 +1445,9 @@ impl Config {
     }
 
     pub async fn update_function(&self, function: Function) -> Result<()> {
+        let uid = function.uid.clone();
+        let function = Self::validate_function(function)
+            .map_err(|errors| Self::function_validation_error(&uid, &errors))?;
         let mut doc = self.document.write().await;
         let functions_array = doc["functions"]
             .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
@@ -974,6 +1481,117 @@ impl Config {
         }
     }
 
+    /// Reads `function_table`'s `version` key (defaulting to 0 for pre-versioning tables), then
+    /// runs every migration from that version up to [`FUNCTION_SCHEMA_VERSION`] in order,
+    /// stamping the new version after each step. Must run before validation/defaulting so a
+    /// migration can introduce a field the validation layer then fills in. Fails loudly, rather
+    /// than partially parsing, when the stored version is newer than this daemon understands.
+    fn migrate_function_table(function_table: &mut Table) -> Result<()> {
+        let mut version = function_table.get("version")
+            .and_then(Item::as_integer)
+            .unwrap_or(0) as u32;
+        if version > FUNCTION_SCHEMA_VERSION {
+            bail!(
+                "Function table schema version {version} is newer than this daemon supports \
+                 (schema version {FUNCTION_SCHEMA_VERSION}). Refusing to load it."
+            );
+        }
+        while version < FUNCTION_SCHEMA_VERSION {
+            let migration = FUNCTION_MIGRATIONS.get(version as usize)
+                .with_context(|| format!("No migration registered from function schema version {version}"))?;
+            migration(function_table);
+            version += 1;
+            function_table["version"] = Item::Value(Value::Integer(Formatted::new(i64::from(version))));
+        }
+        Ok(())
+    }
+
+    /// A default applied when a `Function` field is absent, keyed off `f_type` rather than
+    /// applied unconditionally, so e.g. an `Identity` function isn't given a meaningless
+    /// `sample_window`.
+    const DEFAULT_RESPONSE_DELAY_SECS: u8 = 2;
+    const DEFAULT_DEVIANCE: f64 = 0.5;
+    const DEFAULT_SAMPLE_WINDOW: u16 = 8;
+
+    /// The sane upper bound enforced by [`Self::validate_function`] for each tunable.
+    const MAX_RESPONSE_DELAY_SECS: u8 = 30;
+    const MAX_SAMPLE_WINDOW: u16 = 64;
+
+    /// Validates `function`'s tunable parameters and fills in any missing ones with defaults
+    /// keyed off `f_type`: `SimpleMovingAvg`/`ExponentialMovingAvg` get a default
+    /// `sample_window`, `Standard` gets a default `deviance`/`response_delay`. Out-of-range
+    /// values (a negative `deviance`, a zero or huge `sample_window`, an unreasonable
+    /// `response_delay`) are reported rather than silently clamped, so a hand-edited
+    /// config.toml surfaces the offending key instead of producing undefined behavior in the
+    /// smoothing math.
+    fn validate_function(mut function: Function) -> std::result::Result<Function, Vec<FieldError>> {
+        let mut errors = Vec::new();
+
+        match function.deviance {
+            Some(deviance) if deviance < 0.0 => errors.push(FieldError {
+                field: "deviance",
+                message: format!("deviance must be >= 0.0, got {deviance}"),
+            }),
+            Some(_) => {}
+            None if function.f_type == FunctionType::Standard => {
+                function.deviance = Some(Self::DEFAULT_DEVIANCE);
+            }
+            None => {}
+        }
+
+        match function.sample_window {
+            Some(sample_window) if sample_window == 0 || sample_window > Self::MAX_SAMPLE_WINDOW => {
+                errors.push(FieldError {
+                    field: "sample_window",
+                    message: format!(
+                        "sample_window must be between 1 and {}, got {sample_window}",
+                        Self::MAX_SAMPLE_WINDOW
+                    ),
+                });
+            }
+            Some(_) => {}
+            None if matches!(
+                function.f_type, FunctionType::SimpleMovingAvg | FunctionType::ExponentialMovingAvg
+            ) => {
+                function.sample_window = Some(Self::DEFAULT_SAMPLE_WINDOW);
+            }
+            None => {}
+        }
+
+        match function.response_delay {
+            Some(response_delay) if response_delay > Self::MAX_RESPONSE_DELAY_SECS => {
+                errors.push(FieldError {
+                    field: "response_delay",
+                    message: format!(
+                        "response_delay must be between 0 and {}, got {response_delay}",
+                        Self::MAX_RESPONSE_DELAY_SECS
+                    ),
+                });
+            }
+            Some(_) => {}
+            None if function.f_type == FunctionType::Standard => {
+                function.response_delay = Some(Self::DEFAULT_RESPONSE_DELAY_SECS);
+            }
+            None => {}
+        }
+
+        if errors.is_empty() {
+            Ok(function)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Formats [`Self::validate_function`]'s per-field errors for `function_uid` into a single
+    /// user-facing error, naming each offending key.
+    fn function_validation_error(function_uid: &UID, errors: &[FieldError]) -> anyhow::Error {
+        let details = errors.iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        anyhow!("Invalid function '{function_uid}': {details}")
+    }
+
     fn find_function_in_array(function_uid: &UID, functions_array: &ArrayOfTables) -> Result<Table> {
         for function_table in functions_array.iter() {
             if function_table
@@ -992,24 +1610,49 @@ impl Config {
         new_function
     }
 
+    /// Writes `function`'s fields into `function_table` in place via [`Self::set_function_field`],
+    /// so any existing `Decor` (comments/whitespace) on a known key survives, unknown keys written
+    /// by a newer daemon version are left untouched, and an `Option` field that's now `None` has
+    /// its key removed rather than left stale.
     fn add_function_properties_to_function_table(function: Function, function_table: &mut Table) {
-        function_table["uid"] = Item::Value(Value::String(Formatted::new(function.uid)));
-        function_table["name"] = Item::Value(Value::String(Formatted::new(function.name)));
-        function_table["f_type"] = Item::Value(Value::String(Formatted::new(function.f_type.to_string())));
-        if let Some(response_delay) = function.response_delay {
-            function_table["response_delay"] = Item::Value(
-                Value::Integer(Formatted::new(response_delay as i64))
-            );
-        }
-        if let Some(deviance) = function.deviance {
-            function_table["deviance"] = Item::Value(
-                Value::Float(Formatted::new(deviance))
-            );
-        }
-        if let Some(sample_window) = function.sample_window {
-            function_table["sample_window"] = Item::Value(
-                Value::Integer(Formatted::new(sample_window as i64))
-            );
+        Self::set_function_field(
+            function_table, "version",
+            Some(Value::Integer(Formatted::new(i64::from(FUNCTION_SCHEMA_VERSION)))),
+        );
+        Self::set_function_field(function_table, "uid", Some(Value::String(Formatted::new(function.uid))));
+        Self::set_function_field(function_table, "name", Some(Value::String(Formatted::new(function.name))));
+        Self::set_function_field(
+            function_table, "f_type",
+            Some(Value::String(Formatted::new(function.f_type.to_string()))),
+        );
+        Self::set_function_field(
+            function_table, "response_delay",
+            function.response_delay.map(|delay| Value::Integer(Formatted::new(delay as i64))),
+        );
+        Self::set_function_field(
+            function_table, "deviance",
+            function.deviance.map(|deviance| Value::Float(Formatted::new(deviance))),
+        );
+        Self::set_function_field(
+            function_table, "sample_window",
+            function.sample_window.map(|window| Value::Integer(Formatted::new(window as i64))),
+        );
+    }
+
+    /// Sets `function_table[key]` to `value`, reattaching the `Decor` that was already on that
+    /// key (if any) so a hand-written comment or blank line around it survives the rewrite; when
+    /// `value` is `None`, removes `key` entirely instead of leaving a stale value behind.
+    fn set_function_field(function_table: &mut Table, key: &str, value: Option<Value>) {
+        match value {
+            Some(mut value) => {
+                if let Some(existing) = function_table.get(key).and_then(Item::as_value) {
+                    *value.decor_mut() = existing.decor().clone();
+                }
+                function_table[key] = Item::Value(value);
+            }
+            None => {
+                function_table.remove(key);
+            }
         }
     }
 }
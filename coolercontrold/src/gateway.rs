@@ -0,0 +1,194 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2021-2024  Guy Boldon, Eren Simsek and contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! A control surface for the daemon's IPC gateways. A gateway translates its own wire format
+//! into a [`GatewayCommand`] and hands it to a single [`CommandDispatcher`], so adding a new
+//! command only means adding one dispatch arm rather than one per gateway. The D-Bus service in
+//! [`dbus`] is the only gateway that dispatches through it; the ZMQ socket client does not.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use nix::sys::signal;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::processors::SettingsProcessor;
+use crate::setting::CoolerControlSettings;
+use crate::AllDevices;
+
+/// A control operation accepted by every gateway that dispatches through [`CommandDispatcher`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GatewayCommand {
+    /// Gracefully shuts down the daemon.
+    Shutdown,
+
+    /// Persists and applies the given general CoolerControl settings.
+    ApplySettings(CoolerControlSettings),
+
+    /// Enables or disables ThinkPad full-speed fan control.
+    ThinkPadFanControl { enable: bool },
+
+    /// Returns a snapshot of the current status of every device.
+    QueryStatus,
+}
+
+/// The result of dispatching a [`GatewayCommand`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GatewayResponse {
+    Ack,
+    Status(String),
+    Error(String),
+}
+
+/// Dispatches [`GatewayCommand`]s against the daemon's existing control surfaces
+/// (`Config`, `SettingsProcessor`, `AllDevices`). Gateways hold an `Arc<CommandDispatcher>` and
+/// never touch `Config`/`SettingsProcessor` directly, so the command set stays implemented once.
+pub struct CommandDispatcher {
+    all_devices: AllDevices,
+    settings_processor: Arc<SettingsProcessor>,
+    config: Arc<Config>,
+}
+
+impl CommandDispatcher {
+    pub fn new(
+        all_devices: AllDevices,
+        settings_processor: Arc<SettingsProcessor>,
+        config: Arc<Config>,
+    ) -> Self {
+        Self {
+            all_devices,
+            settings_processor,
+            config,
+        }
+    }
+
+    pub async fn dispatch(&self, command: GatewayCommand) -> GatewayResponse {
+        match self.dispatch_fallible(command).await {
+            Ok(response) => response,
+            Err(err) => GatewayResponse::Error(err.to_string()),
+        }
+    }
+
+    async fn dispatch_fallible(&self, command: GatewayCommand) -> Result<GatewayResponse> {
+        match command {
+            GatewayCommand::Shutdown => {
+                signal::kill(Pid::this(), Signal::SIGQUIT)?;
+                Ok(GatewayResponse::Ack)
+            }
+            GatewayCommand::ApplySettings(settings) => {
+                self.config.set_settings(&settings).await;
+                self.config.save_config_file().await?;
+                Ok(GatewayResponse::Ack)
+            }
+            GatewayCommand::ThinkPadFanControl { enable } => {
+                self.settings_processor.thinkpad_fan_control(&enable).await?;
+                Ok(GatewayResponse::Ack)
+            }
+            GatewayCommand::QueryStatus => {
+                let mut statuses = Vec::new();
+                for (device_uid, device) in self.all_devices.iter() {
+                    if let Some(status) = device.read().await.status_current() {
+                        statuses.push((device_uid.clone(), status));
+                    }
+                }
+                Ok(GatewayResponse::Status(serde_json::to_string(&statuses)?))
+            }
+        }
+    }
+}
+
+/// Exposes the daemon's control surface on D-Bus, as an alternative to the hand-rolled ZMQ
+/// protocol: a proper introspectable interface that `busctl` and desktop integrations can drive
+/// without going through the HTTP server or the bespoke socket in `/tmp/coolercontrol.sock`.
+pub mod dbus {
+    use std::sync::Arc;
+
+    use anyhow::{Context, Result};
+    use log::info;
+    use zbus::{dbus_interface, ConnectionBuilder};
+
+    use super::{CommandDispatcher, GatewayCommand, GatewayResponse};
+    use crate::setting::CoolerControlSettings;
+
+    const DBUS_SERVICE_NAME: &str = "org.coolercontrol.CoolerControlD";
+    const DBUS_OBJECT_PATH: &str = "/org/coolercontrol/CoolerControlD";
+
+    struct DbusControlInterface {
+        dispatcher: Arc<CommandDispatcher>,
+    }
+
+    #[dbus_interface(name = "org.coolercontrol.CoolerControlD")]
+    impl DbusControlInterface {
+        /// Gracefully shuts down the daemon.
+        async fn shutdown(&self) -> zbus::fdo::Result<()> {
+            self.dispatch(GatewayCommand::Shutdown).await.map(|_| ())
+        }
+
+        /// Persists and applies the given general CoolerControl settings, given as JSON.
+        async fn apply_settings(&self, settings_json: String) -> zbus::fdo::Result<()> {
+            let settings: CoolerControlSettings = serde_json::from_str(&settings_json)
+                .map_err(|err| zbus::fdo::Error::InvalidArgs(err.to_string()))?;
+            self.dispatch(GatewayCommand::ApplySettings(settings))
+                .await
+                .map(|_| ())
+        }
+
+        /// Enables or disables ThinkPad full-speed fan control.
+        async fn thinkpad_fan_control(&self, enable: bool) -> zbus::fdo::Result<()> {
+            self.dispatch(GatewayCommand::ThinkPadFanControl { enable })
+                .await
+                .map(|_| ())
+        }
+
+        /// Returns a JSON snapshot of the current status of every device.
+        async fn query_status(&self) -> zbus::fdo::Result<String> {
+            match self.dispatch(GatewayCommand::QueryStatus).await? {
+                GatewayResponse::Status(json) => Ok(json),
+                _ => Ok(String::new()),
+            }
+        }
+    }
+
+    impl DbusControlInterface {
+        async fn dispatch(&self, command: GatewayCommand) -> zbus::fdo::Result<GatewayResponse> {
+            match self.dispatcher.dispatch(command).await {
+                GatewayResponse::Error(msg) => Err(zbus::fdo::Error::Failed(msg)),
+                response => Ok(response),
+            }
+        }
+    }
+
+    /// Starts the D-Bus gateway, registering the control interface on the session bus and
+    /// holding the connection open for the lifetime of the returned handle.
+    pub async fn start(dispatcher: Arc<CommandDispatcher>) -> Result<zbus::Connection> {
+        let connection = ConnectionBuilder::session()
+            .context("Connecting to the D-Bus session bus")?
+            .name(DBUS_SERVICE_NAME)
+            .context("Requesting D-Bus service name")?
+            .serve_at(DBUS_OBJECT_PATH, DbusControlInterface { dispatcher })
+            .context("Registering D-Bus control interface")?
+            .build()
+            .await
+            .context("Building D-Bus connection")?;
+        info!("D-Bus control gateway listening as {DBUS_SERVICE_NAME}");
+        Ok(connection)
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2021-2024  Guy Boldon, Eren Simsek and contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Runs user-defined external commands in response to daemon lifecycle and threshold events
+//! (system sleep/resume, daemon start/stop, a temp or duty crossing a configured threshold).
+//! Mirrors the `LCD_TIMEOUT_S` pattern in `main_loop`: every hook is awaited with a timeout so a
+//! hanging script can't stall whatever fired it.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tokio::time::timeout;
+use zbus::export::futures_util::future::join_all;
+
+const DEFAULT_HOOK_TIMEOUT_S: u64 = 5;
+
+/// The lifecycle/threshold events a hook can be attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HookEvent {
+    DaemonStart,
+    DaemonStop,
+    SystemSleep,
+    SystemResume,
+    ThresholdCrossed,
+}
+
+/// A single user-defined hook: a command run when `event` fires, with its own timeout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookDefinition {
+    pub event: HookEvent,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Context passed to a hook as environment variables, e.g. `COOLERCONTROL_DEVICE_UID`,
+/// `COOLERCONTROL_CHANNEL`, `COOLERCONTROL_TEMP`, `COOLERCONTROL_MODE`. Only the variables
+/// relevant to the firing event need be populated; absent ones are simply not set.
+pub type HookContext = HashMap<&'static str, String>;
+
+/// Spawns the hooks configured for a given event as async child processes and awaits each with a
+/// timeout, logging stdout/stderr. Hooks for the same event run concurrently; one hanging or
+/// failing hook does not prevent the others from running.
+pub struct HookRunner {
+    hooks: Vec<HookDefinition>,
+}
+
+impl HookRunner {
+    pub fn new(hooks: Vec<HookDefinition>) -> Self {
+        Self { hooks }
+    }
+
+    /// Runs every hook registered for `event`, if any, passing `context` as environment
+    /// variables. Returns once all matching hooks have either finished or timed out.
+    pub async fn fire(&self, event: HookEvent, context: &HookContext) {
+        let matching: Vec<&HookDefinition> = self.hooks.iter().filter(|h| h.event == event).collect();
+        if matching.is_empty() {
+            return;
+        }
+        let mut futures = Vec::with_capacity(matching.len());
+        for hook in matching {
+            futures.push(Self::run_one(hook, context));
+        }
+        for result in join_all(futures).await {
+            if let Err(err) = result {
+                error!("Hook execution error: {err}");
+            }
+        }
+    }
+
+    async fn run_one(hook: &HookDefinition, context: &HookContext) -> anyhow::Result<()> {
+        let hook_timeout = Duration::from_secs(hook.timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_S));
+        let mut command = Command::new(&hook.command);
+        command
+            .args(&hook.args)
+            .envs(context.iter().map(|(k, v)| (*k, v.as_str())))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // wait_with_output's future owns the Child; on timeout that future (and the Child
+            // with it) is dropped, and tokio does NOT kill the process on drop by default, so
+            // without this a timed-out hook leaks as an orphan instead of actually being reaped.
+            .kill_on_drop(true);
+        let child = command.spawn()?;
+        match timeout(hook_timeout, child.wait_with_output()).await {
+            Ok(Ok(output)) => {
+                if !output.stdout.is_empty() {
+                    info!("Hook '{}' stdout: {}", hook.command, String::from_utf8_lossy(&output.stdout));
+                }
+                if !output.stderr.is_empty() {
+                    warn!("Hook '{}' stderr: {}", hook.command, String::from_utf8_lossy(&output.stderr));
+                }
+                if !output.status.success() {
+                    warn!("Hook '{}' exited with {}", hook.command, output.status);
+                }
+            }
+            Ok(Err(err)) => error!("Hook '{}' failed to run: {}", hook.command, err),
+            Err(_) => error!("Hook '{}' timed out after {:?}", hook.command, hook_timeout),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2021-2024  Guy Boldon, Eren Simsek and contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Pure image-reduction helpers for an ambient "screen-follow" LCD mode: given a captured
+//! framebuffer, compute either a single dominant/average color or a downscaled tile grid to push
+//! to the device. Kept free of any actual capture dependency so it can run on the existing
+//! off-thread CPU-bound path `LcdCommander::update_lcd` already uses for image reduction.
+
+use serde::{Deserialize, Serialize};
+
+/// Which source `ScreenFollow` mode captures from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScreenFollowCaptureSource {
+    /// The whole desktop / primary output.
+    FullDesktop,
+    /// A specific named output/monitor.
+    Monitor(String),
+}
+
+/// Config for an ambient screen-follow LCD channel: where to capture from, how small to reduce
+/// it to, and whether to collapse everything to one dominant color or keep a tile grid.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenFollowSettings {
+    pub capture_source: ScreenFollowCaptureSource,
+    pub target_width: u32,
+    pub target_height: u32,
+    pub single_dominant_color: bool,
+}
+
+impl Default for ScreenFollowSettings {
+    fn default() -> Self {
+        Self {
+            capture_source: ScreenFollowCaptureSource::FullDesktop,
+            target_width: 16,
+            target_height: 16,
+            single_dominant_color: false,
+        }
+    }
+}
+
+/// The LCD scheduled-setting mode, analogous to the existing image/temp modes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LcdScheduledMode {
+    None,
+    Image,
+    ScreenFollow(ScreenFollowSettings),
+}
+
+/// Averages every pixel in an RGB(A) framebuffer into a single dominant color. `channels` is 3
+/// for RGB or 4 for RGBA (the alpha channel, if present, is ignored).
+pub fn compute_dominant_color(pixels: &[u8], channels: usize) -> (u8, u8, u8) {
+    if pixels.is_empty() || channels == 0 {
+        return (0, 0, 0);
+    }
+    let pixel_count = pixels.len() / channels;
+    if pixel_count == 0 {
+        return (0, 0, 0);
+    }
+    let (mut r_sum, mut g_sum, mut b_sum) = (0u64, 0u64, 0u64);
+    for pixel in pixels.chunks_exact(channels) {
+        r_sum += u64::from(pixel[0]);
+        g_sum += u64::from(pixel[1]);
+        b_sum += u64::from(pixel[2]);
+    }
+    let count = pixel_count as u64;
+    (
+        (r_sum / count) as u8,
+        (g_sum / count) as u8,
+        (b_sum / count) as u8,
+    )
+}
+
+/// Downscales an RGB(A) framebuffer of `src_width` x `src_height` into a `target_cols` x
+/// `target_rows` grid of averaged tile colors, in row-major order. Each output tile is the
+/// average color of the source pixels that fall within it.
+pub fn downscale_tile_grid(
+    pixels: &[u8],
+    src_width: u32,
+    src_height: u32,
+    channels: usize,
+    target_cols: u32,
+    target_rows: u32,
+) -> Vec<(u8, u8, u8)> {
+    if src_width == 0 || src_height == 0 || target_cols == 0 || target_rows == 0 || channels == 0 {
+        return vec![];
+    }
+    let mut tiles = Vec::with_capacity((target_cols * target_rows) as usize);
+    for tile_row in 0..target_rows {
+        let y_start = tile_row * src_height / target_rows;
+        let y_end = ((tile_row + 1) * src_height / target_rows).max(y_start + 1);
+        for tile_col in 0..target_cols {
+            let x_start = tile_col * src_width / target_cols;
+            let x_end = ((tile_col + 1) * src_width / target_cols).max(x_start + 1);
+            let (mut r_sum, mut g_sum, mut b_sum, mut count) = (0u64, 0u64, 0u64, 0u64);
+            for y in y_start..y_end.min(src_height) {
+                let row_start = (y * src_width) as usize * channels;
+                for x in x_start..x_end.min(src_width) {
+                    let pixel_start = row_start + (x as usize) * channels;
+                    if pixel_start + 2 >= pixels.len() {
+                        continue;
+                    }
+                    r_sum += u64::from(pixels[pixel_start]);
+                    g_sum += u64::from(pixels[pixel_start + 1]);
+                    b_sum += u64::from(pixels[pixel_start + 2]);
+                    count += 1;
+                }
+            }
+            tiles.push(if count == 0 {
+                (0, 0, 0)
+            } else {
+                ((r_sum / count) as u8, (g_sum / count) as u8, (b_sum / count) as u8)
+            });
+        }
+    }
+    tiles
+}
@@ -17,92 +17,184 @@
  */
 
 use crate::api::actor::StatusHandle;
+use crate::api::ws::StatusBroadcaster;
+use crate::clock::{with_timeout, Clock};
 use crate::config::Config;
+use crate::hooks::{HookContext, HookEvent, HookRunner};
 use crate::modes::ModeController;
 use crate::processing::settings::SettingsController;
 use crate::sleep_listener::SleepListener;
-use crate::Repos;
+use crate::{AllDevices, Repos};
 use anyhow::{Context, Result};
-use log::{error, info, trace};
+use log::{error, info, trace, warn};
 use moro_local::Scope;
 use std::cell::LazyCell;
+use std::collections::BTreeMap;
 use std::ops::Not;
 use std::rc::Rc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::time;
-use tokio::time::{sleep, timeout};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio_util::sync::CancellationToken;
 
-const LOOP_TICK_DURATION_MS: u64 = 1000;
+/// The default poll period for repos/devices that don't override `Repository::poll_period`, so
+/// existing cadence is unchanged until a repo opts into something faster or slower.
+pub(crate) const LOOP_TICK_DURATION_MS: u64 = 1000;
 const SNAPSHOT_WAIT_MS: u64 = 400;
 const WAKE_PAUSE_MINIMUM_S: u64 = 1;
 const LCD_TIMEOUT_S: u64 = 2;
 const FULL_SECOND_MS: u64 = 1000;
 
+/// A repository slot waiting to fire, keyed in the loop's due-schedule by the `Instant` at which
+/// it next becomes due. Each time it's popped and run, it's reinserted at `key + period`, so a
+/// repo's own period independently controls its cadence instead of being bound to the global
+/// tick. `Repository::poll_period` is the per-repo/device override point: repos that don't
+/// override it inherit `LOOP_TICK_DURATION_MS`, so existing behavior is unchanged until a repo
+/// opts into a faster or slower cadence (e.g. an LCD at 2s, a slow custom sensor at 5s).
+struct DueEntry {
+    repo_index: usize,
+    period: Duration,
+}
+
+/// Pops every schedule entry due at or before `now`, and returns the repo indices that fired this
+/// round along with whether any entry had fallen more than one period behind (e.g. the process was
+/// paused/stalled). Popping them together, rather than one at a time, keeps devices that share a
+/// due instant on the same snapshot so frontend timestamps stay coherent.
+///
+/// Normally an entry is reinserted at `key + period`, preserving its own independent cadence. But
+/// if `now` has drifted more than a full `period` past `key`, reinserting at `key + period` would
+/// still be overdue and the entry would fire again next iteration with no real wait - a burst of
+/// catch-up ticks. Instead we skip the missed ticks and reinsert at `now + period`, so a stall
+/// costs at most one burst tick rather than one per missed period.
+fn pop_due_entries(due_schedule: &mut BTreeMap<Instant, DueEntry>, now: Instant) -> (Vec<usize>, bool) {
+    let due_keys: Vec<Instant> = due_schedule.range(..=now).map(|(key, _)| *key).collect();
+    let mut due_repo_indices = Vec::with_capacity(due_keys.len());
+    let mut any_skipped = false;
+    for key in due_keys {
+        let entry = due_schedule
+            .remove(&key)
+            .expect("key was just observed to be present in the schedule");
+        due_repo_indices.push(entry.repo_index);
+        let next_due = key + entry.period;
+        if next_due <= now {
+            any_skipped = true;
+            warn!(
+                "Repo index {} missed one or more poll ticks (due {:?} ago); skipping ahead instead of bursting",
+                entry.repo_index,
+                now.saturating_duration_since(key)
+            );
+            due_schedule.insert(now + entry.period, entry);
+        } else {
+            due_schedule.insert(next_due, entry);
+        }
+    }
+    (due_repo_indices, any_skipped)
+}
+
 /// Run the main loop of the application.
 ///
 /// This involves periodically checking for changes in the configuration, processing all
 /// devices, and checking for changes in the sleep state of the system.
 ///
 /// The main loop will exit when the application receives a termination signal.
+///
+/// `clock` is the loop's sole time source: production callers pass a `Rc<TokioClock>`, while
+/// tests can pass a `Rc<VirtualClock>` to assert on scheduling behavior (full-second alignment,
+/// every-other-tick LCD updates, snapshot timeouts, `wake_from_sleep`'s `startup_delay`) without
+/// any wall-clock waits.
 pub async fn run<'s>(
     config: Rc<Config>,
     repos: Repos,
+    all_devices: AllDevices,
     settings_controller: Rc<SettingsController>,
     mode_controller: Rc<ModeController>,
     status_handle: StatusHandle,
+    status_broadcaster: Arc<StatusBroadcaster>,
+    hook_runner: Rc<HookRunner>,
+    clock: Rc<dyn Clock>,
     run_token: CancellationToken,
 ) -> Result<()> {
     let snapshot_timeout_duration = LazyCell::new(|| Duration::from_millis(SNAPSHOT_WAIT_MS));
     let mut run_lcd_update = false; // toggle lcd updates every other loop tick
+    hook_runner.fire(HookEvent::DaemonStart, &HookContext::new()).await;
     moro_local::async_scope!(|scope| -> Result<()> {
         let sleep_listener = SleepListener::new(run_token.clone(), scope)
             .await
             .with_context(|| "Creating DBus Sleep Listener")?;
-        align_loop_timing_with_clock().await;
-        // The sub-second position is set on interval creation:
-        let mut loop_interval = time::interval(Duration::from_millis(LOOP_TICK_DURATION_MS));
+        align_loop_timing_with_clock(clock.as_ref()).await;
+        // Every repo becomes due at this same aligned instant the first time, so devices that
+        // want the default full-second-aligned cadence still fire together from the start.
+        let first_due = clock.now();
+        let mut due_schedule: BTreeMap<Instant, DueEntry> = BTreeMap::new();
+        for (repo_index, repo) in repos.iter().enumerate() {
+            due_schedule.insert(
+                first_due,
+                DueEntry {
+                    repo_index,
+                    period: repo.poll_period(),
+                },
+            );
+        }
+        let mut was_preparing_to_sleep = false;
         while run_token.is_cancelled().not() {
-            loop_interval.tick().await;
+            // Sleep until the earliest due repo rather than a fixed tick, so fast repos aren't
+            // held back by slow ones and slow repos aren't polled more often than they asked for.
+            let next_due = *due_schedule
+                .keys()
+                .next()
+                .expect("at least one repo is registered in the due schedule");
+            clock.sleep_until(next_due).await;
+            let (due_repo_indices, any_skipped) = pop_due_entries(&mut due_schedule, clock.now());
+            if any_skipped {
+                // Missed ticks push every repo's timestamps out of full-second alignment; realign
+                // once rather than let the drift compound.
+                align_loop_timing_with_clock(clock.as_ref()).await;
+            }
             if sleep_listener.is_not_preparing_to_sleep() {
+                was_preparing_to_sleep = false;
                 let snapshot_timeout_token = CancellationToken::new();
-                fire_preloads(&repos, snapshot_timeout_token.clone(), scope);
+                fire_preloads(&repos, due_repo_indices.clone(), snapshot_timeout_token.clone(), scope);
                 tokio::select! {
                     // This ensures that our status snapshots are taken a regular intervals,
                     // regardless of how long a particular device's status preload takes.
-                    () = sleep(*snapshot_timeout_duration) => trace!("Snapshot timeout triggered before preload finished"),
+                    () = clock.sleep(*snapshot_timeout_duration) => trace!("Snapshot timeout triggered before preload finished"),
                     () = snapshot_timeout_token.cancelled() => trace!("Preload finished before snapshot timeout"),
                 }
-                fire_snapshots_and_processes(&repos, &settings_controller, run_lcd_update, &status_handle, scope).await;
+                fire_snapshots_and_processes(&repos, &due_repo_indices, &settings_controller, run_lcd_update, &status_handle, &status_broadcaster, &all_devices, clock.as_ref(), scope).await;
                 run_lcd_update = !run_lcd_update;
             } else if sleep_listener.is_resuming() {
+                hook_runner.fire(HookEvent::SystemResume, &HookContext::new()).await;
                 wake_from_sleep(
                     &config,
                     &settings_controller,
                     &mode_controller,
                     &sleep_listener,
+                    clock.as_ref(),
                 )
                 .await?;
+            } else if !was_preparing_to_sleep {
+                was_preparing_to_sleep = true;
+                hook_runner.fire(HookEvent::SystemSleep, &HookContext::new()).await;
             }
         }
+        hook_runner.fire(HookEvent::DaemonStop, &HookContext::new()).await;
         Ok(())
     })
     .await
 }
 
-/// Aligns the main loop's timing with the system clock.
+/// Aligns the main loop's timing with the clock.
 ///
 /// This function calculates the current time in milliseconds since the last full second
 /// and determines how long to wait before the next full second mark. This ensures that
 /// the main loop ticks at a consistent sub-second position, which helps Frontends maintain
 /// consistent timestamps without random start-timing fluctuation.
-async fn align_loop_timing_with_clock() {
+async fn align_loop_timing_with_clock(clock: &dyn Clock) {
     let current_millis = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .subsec_millis();
     let wait_duration = FULL_SECOND_MS - u64::from(current_millis);
-    sleep(Duration::from_millis(wait_duration)).await;
+    clock.sleep(Duration::from_millis(wait_duration)).await;
 }
 
 /// Initiates the preload process for all repositories.
@@ -113,14 +205,15 @@ async fn align_loop_timing_with_clock() {
 /// if completed before the `snapshot_timeout`.
 fn fire_preloads<'s>(
     repos: &'s Repos,
+    due_repo_indices: Vec<usize>,
     snapshot_timeout_token: CancellationToken,
     scope: &'s Scope<'s, 's, Result<()>>,
 ) {
     scope.spawn(async move {
         // This scope ensures that all concurrent preload tasks have completed.
         moro_local::async_scope!(|preload_scope| {
-            for repo in repos.iter() {
-                let repo = Rc::clone(repo);
+            for repo_index in due_repo_indices {
+                let repo = Rc::clone(&repos[repo_index]);
                 preload_scope.spawn(async move {
                     repo.preload_statuses().await;
                 });
@@ -138,20 +231,33 @@ fn fire_preloads<'s>(
 /// LCD updates and `process_scheduled_speeds` to apply any scheduled speed settings.
 async fn fire_snapshots_and_processes<'s>(
     repos: &'s Repos,
+    due_repo_indices: &'s [usize],
     settings_controller: &'s Rc<SettingsController>,
     run_lcd_update: bool,
     status_handle: &'s StatusHandle,
+    status_broadcaster: &'s Arc<StatusBroadcaster>,
+    all_devices: &'s AllDevices,
+    clock: &'s dyn Clock,
     scope: &'s Scope<'s, 's, Result<()>>,
 ) {
-    // snapshots for all devices should be done at the same time. (this is very fast)
-    for repo in repos.iter() {
-        if let Err(err) = repo.update_statuses().await {
+    // Snapshots for every repo due this round should be done at the same time. (this is very
+    // fast) Repos not due this round keep whatever status they last reported.
+    for &repo_index in due_repo_indices {
+        if let Err(err) = repos[repo_index].update_statuses().await {
             error!("Error trying to update status: {err}");
         }
     }
-    fire_lcd_update(settings_controller, run_lcd_update, scope);
+    fire_lcd_update(settings_controller, run_lcd_update, clock, scope);
     settings_controller.process_scheduled_speeds().await;
     status_handle.broadcast_status().await;
+    // Mirrors status_handle.broadcast_status() (in-process subscribers) for WebSocket clients:
+    // without this, /ws/status only ever sends its one on-connect snapshot and never streams a
+    // delta again.
+    for (device_uid, device) in all_devices.iter() {
+        if let Some(status) = device.read().await.status_current() {
+            status_broadcaster.publish(device_uid.clone(), status);
+        }
+    }
 }
 
 /// This function will fire off the LCD Update job which often takes a long time (>1.0s, <2.0s)
@@ -163,6 +269,7 @@ async fn fire_snapshots_and_processes<'s>(
 fn fire_lcd_update<'s>(
     settings_controller: &Rc<SettingsController>,
     run_lcd_update: bool,
+    clock: &'s dyn Clock,
     scope: &'s Scope<'s, 's, Result<()>>,
 ) {
     if run_lcd_update.not()
@@ -176,12 +283,9 @@ fn fire_lcd_update<'s>(
     }
     let lcd_commander = Rc::clone(&settings_controller.lcd_commander);
     scope.spawn(async move {
-        if timeout(
-            Duration::from_secs(LCD_TIMEOUT_S),
-            lcd_commander.update_lcd(),
-        )
-        .await
-        .is_err()
+        if with_timeout(clock, Duration::from_secs(LCD_TIMEOUT_S), lcd_commander.update_lcd())
+            .await
+            .is_err()
         {
             error!("LCD Scheduler timed out after {LCD_TIMEOUT_S}s");
         };
@@ -201,14 +305,16 @@ async fn wake_from_sleep(
     settings_controller: &Rc<SettingsController>,
     mode_controller: &Rc<ModeController>,
     sleep_listener: &SleepListener,
+    clock: &dyn Clock,
 ) -> Result<()> {
-    sleep(
-        config
-            .get_settings()?
-            .startup_delay
-            .max(Duration::from_secs(WAKE_PAUSE_MINIMUM_S)),
-    )
-    .await;
+    clock
+        .sleep(
+            config
+                .get_settings()?
+                .startup_delay
+                .max(Duration::from_secs(WAKE_PAUSE_MINIMUM_S)),
+        )
+        .await;
     if config.get_settings()?.apply_on_boot {
         info!("Re-initializing and re-applying settings after waking from sleep");
         settings_controller.reinitialize_devices().await;
@@ -219,3 +325,64 @@ async fn wake_from_sleep(
     sleep_listener.preparing_to_sleep(false);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn pop_due_entries_reinserts_at_key_plus_period_when_on_schedule() {
+        let mut due_schedule = BTreeMap::new();
+        let start = Instant::now();
+        due_schedule.insert(
+            start,
+            DueEntry {
+                repo_index: 0,
+                period: Duration::from_millis(100),
+            },
+        );
+        let (due, any_skipped) = pop_due_entries(&mut due_schedule, start);
+        assert_eq!(due, vec![0]);
+        assert!(!any_skipped);
+        let (&next_key, _) = due_schedule.iter().next().expect("entry was reinserted");
+        assert_eq!(next_key, start + Duration::from_millis(100));
+    }
+
+    #[test]
+    fn pop_due_entries_skips_ahead_instead_of_bursting_after_a_stall() {
+        let mut due_schedule = BTreeMap::new();
+        let start = Instant::now();
+        due_schedule.insert(
+            start,
+            DueEntry {
+                repo_index: 0,
+                period: Duration::from_millis(100),
+            },
+        );
+        // `now` is well past `key + period`, as if the process had been paused/stalled.
+        let stalled_now = start + Duration::from_millis(500);
+        let (due, any_skipped) = pop_due_entries(&mut due_schedule, stalled_now);
+        assert_eq!(due, vec![0]);
+        assert!(any_skipped);
+        let (&next_key, _) = due_schedule.iter().next().expect("entry was reinserted");
+        assert_eq!(next_key, stalled_now + Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn align_loop_timing_with_clock_sleeps_a_bounded_sub_second_duration() {
+        let clock = std::sync::Arc::new(VirtualClock::new());
+        let advancing_clock = clock.clone();
+        tokio::spawn(async move {
+            // A short real sleep purely to sequence after align_loop_timing_with_clock has
+            // started polling; the wait it's actually exercising is driven by virtual time below,
+            // not wall-clock time, since `FULL_SECOND_MS - current_millis` is always <= 1000ms.
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            advancing_clock.advance(Duration::from_secs(1));
+        });
+        let before = clock.now();
+        align_loop_timing_with_clock(clock.as_ref()).await;
+        let elapsed = clock.now().duration_since(before);
+        assert!(elapsed > Duration::ZERO && elapsed <= Duration::from_secs(1));
+    }
+}
@@ -21,11 +21,13 @@ use std::ops::Not;
 use std::path::Path;
 use std::rc::Rc;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use const_format::concatcp;
 use log::{debug, error, info, trace, warn};
 use moro_local::Scope;
 use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use crate::api::CCError;
@@ -45,7 +47,80 @@ pub struct ModeController {
     settings_controller: Rc<SettingsController>,
     modes: RefCell<HashMap<UID, Mode>>,
     mode_order: RefCell<Vec<UID>>,
-    active_modes: RefCell<Vec<UID>>,
+    mode_active_states: RefCell<Vec<(UID, ModeActiveState)>>,
+    /// UIDs of Modes that have been deleted, so a `merge` with an older `ModeConfigFile` (e.g.
+    /// from another instance or a restored backup) doesn't resurrect them. See
+    /// [`ModeConfigFile::merge`].
+    mode_tombstones: RefCell<Vec<UID>>,
+}
+
+/// Whether a Mode's settings currently match the applied device/channel state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeActiveState {
+    /// Every device and channel the Mode references is present, and all of them match.
+    Active,
+    /// The Mode references at least one device that is no longer present, but every device and
+    /// channel that *is* present still matches - it can never reach `Active` until that device
+    /// comes back.
+    PartiallyActive,
+    /// At least one present device/channel the Mode references doesn't match its settings.
+    Inactive,
+}
+
+/// The outcome of dry-running a Mode's settings against the currently known devices, their
+/// channels, and the configured Profiles, without applying anything. Returned by
+/// [`ModeController::validate_mode`].
+#[derive(Debug, Clone, Default)]
+pub struct ModeValidation {
+    /// Devices referenced by the Mode that are no longer present. A soft failure: the Mode can
+    /// still be applied, that device's settings are simply skipped.
+    pub missing_devices: Vec<DeviceUID>,
+
+    /// `(device_uid, channel_name)` pairs referenced by the Mode whose device is present but no
+    /// longer exposes that channel. Also a soft failure.
+    pub missing_channels: Vec<(DeviceUID, ChannelName)>,
+
+    /// `(device_uid, channel_name, profile_uid)` triples whose Profile no longer exists in the
+    /// config. A hard failure: applying the setting as-is would silently fall back to whatever
+    /// the device happens to be doing, which is never what the user intended.
+    pub missing_profiles: Vec<(DeviceUID, ChannelName, ProfileUID)>,
+}
+
+impl ModeValidation {
+    /// Whether the Mode can be safely applied. Missing devices/channels are tolerated (that
+    /// portion of the Mode is skipped), but a missing Profile is a hard failure.
+    pub fn can_apply(&self) -> bool {
+        self.missing_profiles.is_empty()
+    }
+}
+
+/// A structured event emitted by [`ModeController::activate_mode_with_progress`] as a Mode's
+/// settings are applied, so the API layer can stream activation progress to the UI instead of
+/// only learning success/failure after everything has joined.
+#[derive(Debug, Clone)]
+pub enum ModeActivationEvent {
+    /// Emitted once, before anything is applied, with the number of settings about to be applied
+    /// (an upper bound: a setting that's already at its target value is skipped, not counted).
+    Started { total: usize },
+
+    /// Emitted as each `(device_uid, channel_name)` setting finishes, successfully or not.
+    ChannelApplied {
+        device_uid: DeviceUID,
+        channel_name: ChannelName,
+        error: Option<String>,
+    },
+
+    /// Emitted once after every setting has been applied, listing everything that failed.
+    Finished { failed: Vec<(DeviceUID, ChannelName)> },
+}
+
+/// A channel `Setting` resolved through Mode inheritance, along with the UID of the Mode that
+/// actually defined it - the Mode itself for an explicit setting, or an ancestor's UID for one
+/// that was only inherited.
+#[derive(Debug, Clone)]
+pub struct ResolvedSetting {
+    pub setting: Setting,
+    pub source_mode_uid: UID,
 }
 
 impl ModeController {
@@ -61,7 +136,8 @@ impl ModeController {
             settings_controller,
             modes: RefCell::new(HashMap::new()),
             mode_order: RefCell::new(Vec::new()),
-            active_modes: RefCell::new(Vec::new()),
+            mode_active_states: RefCell::new(Vec::new()),
+            mode_tombstones: RefCell::new(Vec::new()),
         };
         mode_controller.fill_data_from_mode_config_file().await?;
         Ok(mode_controller)
@@ -129,6 +205,7 @@ impl ModeController {
             let default_mode_config = serde_json::to_string(&ModeConfigFile {
                 modes: Vec::new(),
                 order: Vec::new(),
+                tombstones: Vec::new(),
             })?;
             cc_fs::write_string(&path, default_mode_config)
                 .await
@@ -138,8 +215,21 @@ impl ModeController {
                 .await
                 .with_context(|| format!("Reading configuration file {path:?}"))?
         };
-        let mode_config: ModeConfigFile = serde_json::from_str(&config_contents)
-            .with_context(|| format!("Parsing Mode configuration file {path:?}"))?;
+        let mode_config: ModeConfigFile = match serde_json::from_str(&config_contents) {
+            Ok(mode_config) => mode_config,
+            Err(err) => {
+                let bak_path = path.with_extension("json.bak");
+                let bak_contents = cc_fs::read_txt(&bak_path)
+                    .await
+                    .with_context(|| format!("Parsing Mode configuration file {path:?} failed ({err}), and no backup was found at {bak_path:?}"))?;
+                let mode_config = serde_json::from_str(&bak_contents)
+                    .with_context(|| format!("Parsing Mode configuration backup {bak_path:?}"))?;
+                warn!(
+                    "Mode configuration file {path:?} failed to parse ({err}), recovered from backup {bak_path:?}"
+                );
+                mode_config
+            }
+        };
         {
             let mut modes_lock = self.modes.borrow_mut();
             modes_lock.clear();
@@ -152,6 +242,11 @@ impl ModeController {
             mode_order_lock.clear();
             mode_order_lock.extend(mode_config.order);
         }
+        {
+            let mut tombstones_lock = self.mode_tombstones.borrow_mut();
+            tombstones_lock.clear();
+            tombstones_lock.extend(mode_config.tombstones);
+        }
         Ok(())
     }
 
@@ -168,20 +263,81 @@ impl ModeController {
         self.modes.borrow().get(mode_uid).cloned()
     }
 
+    /// Returns every Mode tagged with `group`, in `mode_order`.
+    pub fn modes_in_group(&self, group: &str) -> Vec<Mode> {
+        self.get_modes()
+            .into_iter()
+            .filter(|mode| mode.groups.iter().any(|g| g == group))
+            .collect()
+    }
+
+    /// Merges the effective settings (see [`Self::resolve_effective_settings`]) of every Mode in
+    /// `group` into a single `all_device_settings` map, so a whole group can be activated at
+    /// once. Conflicts between Modes in the group are resolved in `mode_order`, with later
+    /// members overriding earlier ones - the same "last one wins" precedence used for Mode
+    /// inheritance.
+    pub fn resolve_group_settings(
+        &self,
+        group: &str,
+    ) -> Result<HashMap<DeviceUID, HashMap<ChannelName, Setting>>> {
+        let mut merged: HashMap<DeviceUID, HashMap<ChannelName, Setting>> = HashMap::new();
+        for mode in self.modes_in_group(group) {
+            let mode_settings = self.effective_all_device_settings(&mode.uid)?;
+            for (device_uid, channel_settings) in mode_settings {
+                merged.entry(device_uid).or_default().extend(channel_settings);
+            }
+        }
+        Ok(merged)
+    }
+
     /// Returns the currently active Modes.
-    pub fn determine_active_modes_uids(&self) -> Vec<UID> {
+    pub fn determine_active_modes_uids(&self) -> Vec<(UID, ModeActiveState)> {
         self.determine_active_modes();
-        self.active_modes.borrow().clone()
+        self.mode_active_states.borrow().clone()
     }
 
-    /// Determines the active modes and sets them.
+    /// Determines each Mode's active state and sets them.
     fn determine_active_modes(&self) {
-        // todo: I've noticed a bug, where if there are missing devices for a Mode, it will be considered active.
-        //  - In my case, I reset my config and didn't connect to liquidctl. Almost all my modes
-        //    are considered active now because nearly all the devices I had settings for don't exist anymore.
-        let mut active_modes = Vec::new();
+        let mut mode_states = Vec::new();
         let modes = self.modes.borrow();
         'modes: for (mode_uid, mode) in modes.iter() {
+            // A Mode that references a device no longer in `all_devices` can never be fully
+            // Active - at best, every *present* device/channel it references still matches
+            // (PartiallyActive); this is what fixes the previous "nearly all my modes are
+            // considered active" bug, where a missing device was silently ignored instead of
+            // capping the result below `Active`.
+            let has_missing_device = mode
+                .all_device_settings
+                .keys()
+                .any(|device_uid| self.all_devices.contains_key(device_uid).not());
+            if mode.mode_type == ModeType::Overlay {
+                // An Overlay Mode never touches channels it doesn't list, so it's active as long
+                // as its own listed channel settings match, regardless of anything else.
+                for (device_uid, mode_channel_settings) in &mode.all_device_settings {
+                    if self.all_devices.contains_key(device_uid).not() {
+                        // Already accounted for in has_missing_device; nothing present to check.
+                        continue;
+                    }
+                    let current_channel_settings =
+                        self.config.get_device_settings(device_uid).unwrap_or_default();
+                    for (channel_name, mode_setting) in mode_channel_settings {
+                        let current_setting = current_channel_settings
+                            .iter()
+                            .find(|setting| &setting.channel_name == channel_name);
+                        match current_setting {
+                            Some(current_setting) if current_setting == mode_setting => {}
+                            _ => continue 'modes,
+                        }
+                    }
+                }
+                let state = if has_missing_device {
+                    ModeActiveState::PartiallyActive
+                } else {
+                    ModeActiveState::Active
+                };
+                mode_states.push((mode_uid.clone(), state));
+                continue 'modes;
+            }
             'currently_present_devices: for device_uid in self.all_devices.keys() {
                 let current_channel_settings = self.config.get_device_settings(device_uid).unwrap();
                 if mode.all_device_settings.contains_key(device_uid).not() {
@@ -241,30 +397,217 @@ impl ModeController {
                 }
             }
             // All applicable device & channel settings are a match
-            active_modes.push(mode_uid.clone());
+            let state = if has_missing_device {
+                ModeActiveState::PartiallyActive
+            } else {
+                ModeActiveState::Active
+            };
+            mode_states.push((mode_uid.clone(), state));
         }
-        if active_modes.is_empty() {
-            self.active_modes.borrow_mut().clear();
-            debug!("No mode is currently active");
+        if mode_states.is_empty() {
+            self.mode_active_states.borrow_mut().clear();
+            debug!("No mode is currently active or partially active");
             return;
         }
-        debug!("Active modes determined: {active_modes:?}");
-        self.update_active_modes(active_modes);
+        debug!("Mode active states determined: {mode_states:?}");
+        self.update_active_modes(mode_states);
     }
 
     fn is_default_profile(profile_uid: Option<&ProfileUID>) -> bool {
         profile_uid.map_or(false, |uid| uid == DEFAULT_PROFILE_UID)
     }
 
-    fn update_active_modes(&self, mut active_modes: Vec<UID>) {
-        let mut active_modes_lock = self.active_modes.borrow_mut();
-        active_modes_lock.clear();
-        active_modes_lock.append(&mut active_modes);
+    /// Resolves a Mode's settings through its `parent_uids` chain, depth-first in declared order,
+    /// with each Mode's own `all_device_settings` taking precedence over anything inherited from
+    /// its parents. Only the explicit `all_device_settings` and `parent_uids` are ever persisted;
+    /// this is the "effective" view computed on demand, analogous to ClickHouse's
+    /// `substituteProfiles`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `mode_uid` is not found, or if the parent chain cycles back on itself.
+    pub fn resolve_effective_settings(
+        &self,
+        mode_uid: &UID,
+    ) -> Result<HashMap<DeviceUID, HashMap<ChannelName, ResolvedSetting>>> {
+        let modes = self.modes.borrow();
+        let mut visiting = Vec::new();
+        Self::resolve_effective_settings_inner(&modes, mode_uid, &mut visiting)
+    }
+
+    fn resolve_effective_settings_inner(
+        modes: &HashMap<UID, Mode>,
+        mode_uid: &UID,
+        visiting: &mut Vec<UID>,
+    ) -> Result<HashMap<DeviceUID, HashMap<ChannelName, ResolvedSetting>>> {
+        if visiting.contains(mode_uid) {
+            let mut cycle = visiting.clone();
+            cycle.push(mode_uid.clone());
+            bail!("Mode inheritance cycle detected: {}", cycle.join(" -> "));
+        }
+        let mode = modes
+            .get(mode_uid)
+            .ok_or_else(|| anyhow!("Mode not found: {mode_uid}"))?;
+        visiting.push(mode_uid.clone());
+        let mut effective: HashMap<DeviceUID, HashMap<ChannelName, ResolvedSetting>> =
+            HashMap::new();
+        for parent_uid in &mode.parent_uids {
+            let parent_settings =
+                Self::resolve_effective_settings_inner(modes, parent_uid, visiting)?;
+            for (device_uid, channel_settings) in parent_settings {
+                effective
+                    .entry(device_uid)
+                    .or_default()
+                    .extend(channel_settings);
+            }
+        }
+        for (device_uid, channel_settings) in &mode.all_device_settings {
+            let device_entry = effective.entry(device_uid.clone()).or_default();
+            for (channel_name, setting) in channel_settings {
+                device_entry.insert(
+                    channel_name.clone(),
+                    ResolvedSetting {
+                        setting: setting.clone(),
+                        source_mode_uid: mode_uid.clone(),
+                    },
+                );
+            }
+        }
+        visiting.pop();
+        Ok(effective)
+    }
+
+    /// Same as [`Self::resolve_effective_settings`], but strips the inheritance provenance for
+    /// callers, such as [`Self::validate_mode`] and [`Self::activate_mode_with_progress`], that
+    /// only care about the final, merged `Setting` values.
+    fn effective_all_device_settings(
+        &self,
+        mode_uid: &UID,
+    ) -> Result<HashMap<DeviceUID, HashMap<ChannelName, Setting>>> {
+        Ok(self
+            .resolve_effective_settings(mode_uid)?
+            .into_iter()
+            .map(|(device_uid, channel_settings)| {
+                let channel_settings = channel_settings
+                    .into_iter()
+                    .map(|(channel_name, resolved)| (channel_name, resolved.setting))
+                    .collect();
+                (device_uid, channel_settings)
+            })
+            .collect())
+    }
+
+    /// Compares `mode_uid`'s effective settings (see [`Self::resolve_effective_settings`]) against
+    /// `currently_applied`, returning only the `(device_uid, channel_name, setting)` tuples whose
+    /// target `Setting` differs from what's already applied. Channels that are byte-for-byte
+    /// identical are skipped, so [`Self::activate_mode_with_progress`] never re-writes hardware
+    /// that's already in the right state - e.g. re-flashing an RGB controller or re-sending a fan
+    /// curve that hasn't changed.
+    pub fn diff_against_applied(
+        &self,
+        mode_uid: &UID,
+        currently_applied: &HashMap<DeviceUID, HashMap<ChannelName, Setting>>,
+    ) -> Result<Vec<(DeviceUID, ChannelName, Setting)>> {
+        let effective_settings = self.effective_all_device_settings(mode_uid)?;
+        let mut delta = Vec::new();
+        for (device_uid, channel_settings) in &effective_settings {
+            let applied_channel_settings = currently_applied.get(device_uid);
+            for (channel_name, setting) in
+                Self::changed_settings(applied_channel_settings, channel_settings)
+            {
+                delta.push((device_uid.clone(), channel_name, setting));
+            }
+        }
+        Ok(delta)
+    }
+
+    /// The channels in `target` whose `Setting` is absent from, or different than, `applied`.
+    /// Shared by [`Self::diff_against_applied`] and [`Self::apply_mode_channel_settings`] so there
+    /// is exactly one definition of "has this channel's setting actually changed".
+    fn changed_settings<'a>(
+        applied: Option<&'a HashMap<ChannelName, Setting>>,
+        target: &'a HashMap<ChannelName, Setting>,
+    ) -> impl Iterator<Item = (ChannelName, Setting)> + 'a {
+        target.iter().filter_map(move |(channel_name, setting)| {
+            let unchanged = applied
+                .and_then(|settings| settings.get(channel_name))
+                .map_or(false, |applied_setting| applied_setting == setting);
+            unchanged.not().then(|| (channel_name.clone(), setting.clone()))
+        })
+    }
+
+    /// Dry-runs a Mode's settings against the currently known devices, their channels, and the
+    /// configured Profiles, without applying anything. Used by [`Self::activate_mode`] to refuse
+    /// a half-broken Mode before it touches any hardware.
+    pub async fn validate_mode(&self, mode_uid: &UID) -> Result<ModeValidation> {
+        let Some(mode) = self.modes.borrow().get(mode_uid).cloned() else {
+            return Err(CCError::NotFound {
+                msg: format!("Mode not found: {mode_uid}"),
+            }
+            .into());
+        };
+        let effective_settings = self.effective_all_device_settings(mode_uid)?;
+        let profiles = self.config.get_profiles().await?;
+        let mut validation = ModeValidation::default();
+        for (device_uid, channel_settings) in &effective_settings {
+            let Some(device) = self.all_devices.get(device_uid) else {
+                validation.missing_devices.push(device_uid.clone());
+                continue;
+            };
+            let device_channels = device
+                .borrow()
+                .status_current()
+                .map(|status| status.channels.clone())
+                .unwrap_or_default();
+            for (channel_name, setting) in channel_settings {
+                if device_channels
+                    .iter()
+                    .any(|channel| &channel.name == channel_name)
+                    .not()
+                {
+                    validation
+                        .missing_channels
+                        .push((device_uid.clone(), channel_name.clone()));
+                }
+                if let Some(profile_uid) = setting.profile_uid.as_ref() {
+                    let profile_resolves = Self::is_default_profile(Some(profile_uid))
+                        || profiles.iter().any(|profile| &profile.uid == profile_uid);
+                    if profile_resolves.not() {
+                        validation.missing_profiles.push((
+                            device_uid.clone(),
+                            channel_name.clone(),
+                            profile_uid.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(validation)
+    }
+
+    fn update_active_modes(&self, mut mode_states: Vec<(UID, ModeActiveState)>) {
+        let mut mode_active_states_lock = self.mode_active_states.borrow_mut();
+        mode_active_states_lock.clear();
+        mode_active_states_lock.append(&mut mode_states);
     }
 
     /// Takes a Mode UID and applies all it's saved settings, making it the active Mode.
     /// This method handles several edge cases and unknowns.
     pub async fn activate_mode(&self, mode_uid: &UID) -> Result<()> {
+        self.activate_mode_with_progress(mode_uid, None).await
+    }
+
+    /// Same as [`Self::activate_mode`], but if `progress` is given, emits a
+    /// [`ModeActivationEvent`] for the total number of settings about to be applied, each channel
+    /// as it succeeds or fails, and a final summary, so the API layer can stream activation
+    /// progress to the UI rather than only learning the outcome once everything has joined.
+    /// Failed channels are accumulated and reported via the returned `Result` instead of only
+    /// being logged, so a partially-failed activation is surfaced to the caller.
+    pub async fn activate_mode_with_progress(
+        &self,
+        mode_uid: &UID,
+        progress: Option<mpsc::UnboundedSender<ModeActivationEvent>>,
+    ) -> Result<()> {
         let Some(mode) = self.modes.borrow().get(mode_uid).cloned() else {
             error!("Mode not found: {}", mode_uid);
             return Err(CCError::NotFound {
@@ -272,15 +615,64 @@ impl ModeController {
             }
             .into());
         };
-        if self.active_modes.borrow().contains(mode_uid) {
+        let is_fully_active = self
+            .mode_active_states
+            .borrow()
+            .iter()
+            .any(|(uid, state)| uid == mode_uid && *state == ModeActiveState::Active);
+        if is_fully_active {
             debug!("Mode already active: {} ID:{mode_uid}", mode.name);
             return Ok(());
         }
+        let validation = self.validate_mode(mode_uid).await?;
+        if validation.can_apply().not() {
+            let msg = format!(
+                "Mode {} cannot be applied, it references Profiles that no longer exist: {:?}",
+                mode.name, validation.missing_profiles
+            );
+            error!("{msg}");
+            return Err(CCError::UserError { msg }.into());
+        }
+        if validation.missing_devices.is_empty().not() || validation.missing_channels.is_empty().not() {
+            warn!(
+                "Mode {} references devices/channels that no longer exist and will be skipped: \
+                 missing devices: {:?}, missing channels: {:?}",
+                mode.name, validation.missing_devices, validation.missing_channels
+            );
+        }
 
+        let effective_settings = self.effective_all_device_settings(mode_uid)?;
+        let is_overlay = mode.mode_type == ModeType::Overlay;
+        if let Some(sender) = &progress {
+            let total = self.count_settings_to_apply(&effective_settings, is_overlay);
+            let _ = sender.send(ModeActivationEvent::Started { total });
+        }
+        let failed: Rc<RefCell<Vec<(DeviceUID, ChannelName)>>> = Rc::new(RefCell::new(Vec::new()));
         moro_local::async_scope!(|scope| -> Result<()> {
+            if is_overlay {
+                // An Overlay Mode only ever touches the channels it explicitly lists, so unlike
+                // a Full Mode there's nothing to reset for devices/channels it doesn't mention.
+                for (device_uid, mode_device_settings) in &effective_settings {
+                    let mut settings_tuples = Vec::new();
+                    for setting in self.config.get_device_settings(device_uid)? {
+                        settings_tuples.push((setting.channel_name.clone(), setting));
+                    }
+                    let saved_device_settings_map: HashMap<ChannelName, Setting> =
+                        settings_tuples.into_iter().collect();
+                    self.apply_mode_channel_settings(
+                        device_uid,
+                        &saved_device_settings_map,
+                        mode_device_settings,
+                        scope,
+                        progress.clone(),
+                        Rc::clone(&failed),
+                    );
+                }
+                return Ok(());
+            }
             for device_uid in self.all_devices.keys() {
-                if mode.all_device_settings.contains_key(device_uid).not() {
-                    self.reset_device_settings(device_uid, scope)?;
+                if effective_settings.contains_key(device_uid).not() {
+                    self.reset_device_settings(device_uid, scope, progress.clone(), Rc::clone(&failed))?;
                     continue;
                 }
                 let mut settings_tuples = Vec::new();
@@ -289,32 +681,110 @@ impl ModeController {
                 }
                 let saved_device_settings_map: HashMap<ChannelName, Setting> =
                     settings_tuples.into_iter().collect();
-                let mode_device_settings = mode.all_device_settings.get(device_uid).unwrap();
+                let mode_device_settings = effective_settings.get(device_uid).unwrap();
                 self.reset_unset_mode_channels(
                     device_uid,
                     &saved_device_settings_map,
                     mode_device_settings,
                     scope,
+                    progress.clone(),
+                    Rc::clone(&failed),
                 );
                 self.apply_mode_channel_settings(
                     device_uid,
                     &saved_device_settings_map,
                     mode_device_settings,
                     scope,
+                    progress.clone(),
+                    Rc::clone(&failed),
                 );
             }
             Ok(())
         })
         .await?;
         self.config.save_config_file().await?;
-        debug!("Mode applied: {}", mode.name);
-        Ok(())
+        let failed = Rc::try_unwrap(failed)
+            .map(RefCell::into_inner)
+            .unwrap_or_default();
+        if let Some(sender) = &progress {
+            let _ = sender.send(ModeActivationEvent::Finished {
+                failed: failed.clone(),
+            });
+        }
+        if failed.is_empty() {
+            debug!("Mode applied: {}", mode.name);
+            return Ok(());
+        }
+        let msg = format!(
+            "Mode {} partially failed to apply; channels that failed: {failed:?}",
+            mode.name
+        );
+        error!("{msg}");
+        Err(CCError::UserError { msg }.into())
+    }
+
+    /// An upper-bound count of the settings `activate_mode_with_progress` is about to apply, for
+    /// the `Started` progress event. A setting already at its target value is skipped rather than
+    /// spawned, so the real number of `ChannelApplied` events can be lower than this count.
+    fn count_settings_to_apply(
+        &self,
+        effective_settings: &HashMap<DeviceUID, HashMap<ChannelName, Setting>>,
+        is_overlay: bool,
+    ) -> usize {
+        if is_overlay {
+            return effective_settings.values().map(HashMap::len).sum();
+        }
+        let mut total = 0;
+        for device_uid in self.all_devices.keys() {
+            let Ok(current_channel_settings) = self.config.get_device_settings(device_uid) else {
+                continue;
+            };
+            match effective_settings.get(device_uid) {
+                None => total += current_channel_settings.len(),
+                Some(mode_device_settings) => {
+                    total += current_channel_settings
+                        .iter()
+                        .filter(|setting| {
+                            mode_device_settings
+                                .contains_key(&setting.channel_name)
+                                .not()
+                        })
+                        .count();
+                    total += mode_device_settings.len();
+                }
+            }
+        }
+        total
+    }
+
+    /// Sends a `ChannelApplied` progress event, if `progress` is set, and records `device_uid`/
+    /// `channel_name` in `failed` if `result` was an error.
+    fn report_channel_result(
+        progress: &Option<mpsc::UnboundedSender<ModeActivationEvent>>,
+        failed: &Rc<RefCell<Vec<(DeviceUID, ChannelName)>>>,
+        device_uid: DeviceUID,
+        channel_name: ChannelName,
+        result: Result<()>,
+    ) {
+        let error = result.err().map(|err| err.to_string());
+        if error.is_some() {
+            failed.borrow_mut().push((device_uid.clone(), channel_name.clone()));
+        }
+        if let Some(sender) = progress {
+            let _ = sender.send(ModeActivationEvent::ChannelApplied {
+                device_uid,
+                channel_name,
+                error,
+            });
+        }
     }
 
     fn reset_device_settings<'s>(
         &self,
         device_uid: &DeviceUID,
         scope: &'s Scope<'s, 's, Result<()>>,
+        progress: Option<mpsc::UnboundedSender<ModeActivationEvent>>,
+        failed: Rc<RefCell<Vec<(DeviceUID, ChannelName)>>>,
     ) -> Result<()> {
         let saved_device_settings = self.config.get_device_settings(device_uid)?;
         for setting in saved_device_settings {
@@ -327,15 +797,19 @@ impl ModeController {
                 reset_to_default: Some(true),
                 ..Default::default()
             };
+            let progress = progress.clone();
+            let failed = Rc::clone(&failed);
             scope.spawn(async move {
                 debug!("Applying RESET Mode Setting: {reset_setting:?} to device: {device_uid}");
-                if let Err(err) = settings_controller
+                let result = settings_controller
                     .set_reset(&device_uid, &channel_name)
-                    .await
-                {
+                    .await;
+                if let Err(err) = &result {
                     error!("Error setting device setting: {err}");
+                } else {
+                    config.set_device_setting(&device_uid, &reset_setting);
                 }
-                config.set_device_setting(&device_uid, &reset_setting);
+                Self::report_channel_result(&progress, &failed, device_uid, channel_name, result);
             });
         }
         Ok(())
@@ -347,6 +821,8 @@ impl ModeController {
         saved_device_settings_map: &HashMap<ChannelName, Setting>,
         mode_device_settings: &HashMap<ChannelName, Setting>,
         scope: &'s Scope<'s, 's, Result<()>>,
+        progress: Option<mpsc::UnboundedSender<ModeActivationEvent>>,
+        failed: Rc<RefCell<Vec<(DeviceUID, ChannelName)>>>,
     ) {
         for saved_setting_channel_name in saved_device_settings_map.keys() {
             if mode_device_settings
@@ -364,15 +840,19 @@ impl ModeController {
                     reset_to_default: Some(true),
                     ..Default::default()
                 };
+                let progress = progress.clone();
+                let failed = Rc::clone(&failed);
                 scope.spawn(async move {
                     debug!("Applying Mode Setting: {reset_setting:?} to device: {device_uid}");
-                    if let Err(err) = settings_controller
+                    let result = settings_controller
                         .set_reset(&device_uid, &channel_name)
-                        .await
-                    {
+                        .await;
+                    if let Err(err) = &result {
                         error!("Error setting device setting: {err}");
+                    } else {
+                        config.set_device_setting(&device_uid, &reset_setting);
                     }
-                    config.set_device_setting(&device_uid, &reset_setting);
+                    Self::report_channel_result(&progress, &failed, device_uid, channel_name, result);
                 });
             }
         }
@@ -384,29 +864,30 @@ impl ModeController {
         saved_device_settings_map: &HashMap<ChannelName, Setting>,
         mode_device_settings: &HashMap<ChannelName, Setting>,
         scope: &'s Scope<'s, 's, Result<()>>,
+        progress: Option<mpsc::UnboundedSender<ModeActivationEvent>>,
+        failed: Rc<RefCell<Vec<(DeviceUID, ChannelName)>>>,
     ) {
-        for (channel_name, setting) in mode_device_settings {
-            if saved_device_settings_map
-                .get(channel_name)
-                .map_or(false, |saved_setting| saved_setting == setting)
-            {
-                continue; // no need to apply if the setting is the same
-            }
+        for (_, setting) in
+            Self::changed_settings(Some(saved_device_settings_map), mode_device_settings)
+        {
             let settings_controller = Rc::clone(&self.settings_controller);
             let config = Rc::clone(&self.config);
             let device_uid = device_uid.clone();
-            let setting = setting.clone();
+            let progress = progress.clone();
+            let failed = Rc::clone(&failed);
             scope.spawn(async move {
                 debug!("Applying Mode Setting: {setting:?} to device: {device_uid}");
-                if let Err(err) = settings_controller
+                let channel_name = setting.channel_name.clone();
+                let result = settings_controller
                     .set_config_setting(&device_uid, &setting)
-                    .await
-                {
+                    .await;
+                if let Err(err) = &result {
                     error!("Error setting device setting: {err}");
-                    return; // don't save setting if it wasn't successfully applied
+                } else {
+                    debug!("Device Setting Applied: {setting:?}");
+                    config.set_device_setting(&device_uid, &setting);
                 }
-                debug!("Device Setting Applied: {setting:?}");
-                config.set_device_setting(&device_uid, &setting);
+                Self::report_channel_result(&progress, &failed, device_uid, channel_name, result);
             });
         }
     }
@@ -420,6 +901,10 @@ impl ModeController {
             uid: mode_uid.clone(),
             name,
             all_device_settings,
+            mode_type: ModeType::Full,
+            parent_uids: Vec::new(),
+            groups: Vec::new(),
+            version: 1,
         };
         {
             // force a lock release after inserting
@@ -445,6 +930,10 @@ impl ModeController {
                 uid: Uuid::new_v4().to_string(),
                 name: format!("{} (copy)", mode_to_dup.name),
                 all_device_settings: mode_to_dup.all_device_settings.clone(),
+                mode_type: mode_to_dup.mode_type,
+                parent_uids: mode_to_dup.parent_uids.clone(),
+                groups: mode_to_dup.groups.clone(),
+                version: 1,
             }
         };
         {
@@ -482,6 +971,7 @@ impl ModeController {
                     msg: format!("Mode not found: {mode_uid}"),
                 })?;
             mode.name = name;
+            mode.version += 1;
         }
         self.save_modes_data().await?;
         Ok(())
@@ -497,6 +987,7 @@ impl ModeController {
                     msg: format!("Mode not found: {mode_uid}"),
                 })?;
             mode.all_device_settings = self.get_all_device_settings()?;
+            mode.version += 1;
             mode.clone()
         };
         self.save_modes_data().await?;
@@ -520,49 +1011,209 @@ impl ModeController {
         Ok(())
     }
 
+    /// Returns the UIDs of every Mode whose `parent_uids` references `mode_uid`.
+    fn modes_inheriting_from(&self, mode_uid: &UID) -> Vec<UID> {
+        self.modes
+            .borrow()
+            .values()
+            .filter(|mode| mode.parent_uids.iter().any(|parent_uid| parent_uid == mode_uid))
+            .map(|mode| mode.uid.clone())
+            .collect()
+    }
+
     /// Deletes a mode from the `ModeController` with the given Mode UID.
-    pub async fn delete_mode(&self, mode_uid: &UID) -> Result<()> {
+    ///
+    /// Unless `force` is set, this refuses to delete a Mode still referenced by another Mode's
+    /// `parent_uids`, returning a `CCError::UserError` describing the referencing Modes - without
+    /// this, `resolve_effective_settings` would permanently fail for any Mode that inherited from
+    /// the one just deleted, with no recovery path. With `force` set (or no references at all),
+    /// the dangling `parent_uids` entry is stripped from every referencing Mode. Mirrors
+    /// `profile_deleted`'s handling of the analogous Profile case.
+    pub async fn delete_mode(&self, mode_uid: &UID, force: bool) -> Result<()> {
         if self.modes.borrow().contains_key(mode_uid).not() {
             return Err(CCError::NotFound {
                 msg: format!("Mode not found: {mode_uid}"),
             }
             .into());
         }
+        let inheriting_mode_uids = self.modes_inheriting_from(mode_uid);
+        if inheriting_mode_uids.is_empty().not() && force.not() {
+            let modes = self.modes.borrow();
+            let mut inheriting_mode_names: Vec<String> = inheriting_mode_uids
+                .iter()
+                .map(|uid| {
+                    modes
+                        .get(uid)
+                        .map_or_else(|| uid.clone(), |mode| mode.name.clone())
+                })
+                .collect();
+            inheriting_mode_names.sort();
+            drop(modes);
+            return Err(CCError::UserError {
+                msg: format!(
+                    "Mode {mode_uid} is still referenced as a parent by {} mode(s), in: {inheriting_mode_names:?}. \
+                     Pass force=true to delete the Mode and strip those references anyway.",
+                    inheriting_mode_uids.len()
+                ),
+            }
+            .into());
+        }
         {
-            self.modes.borrow_mut().remove(mode_uid);
+            let mut modes_lock = self.modes.borrow_mut();
+            modes_lock.remove(mode_uid);
+            for inheriting_mode_uid in &inheriting_mode_uids {
+                if let Some(inheriting_mode) = modes_lock.get_mut(inheriting_mode_uid) {
+                    inheriting_mode.parent_uids.retain(|parent_uid| parent_uid != mode_uid);
+                    inheriting_mode.version += 1;
+                }
+            }
             self.mode_order.borrow_mut().retain(|uid| uid != mode_uid);
+            let mut tombstones_lock = self.mode_tombstones.borrow_mut();
+            if tombstones_lock.contains(mode_uid).not() {
+                tombstones_lock.push(mode_uid.clone());
+            }
         }
         self.save_modes_data().await?;
         Ok(())
     }
 
     /// Saves the current Modes data to the Mode configuration file.
+    /// Serializes the current Modes and writes them out crash-safely: the previous good file
+    /// (if any) is preserved as `modes.json.bak`, and the new content is written to
+    /// `modes.json.tmp`, fsynced, and only then renamed over `modes.json`, so a power loss or
+    /// panic mid-write can never corrupt the live file.
     async fn save_modes_data(&self) -> Result<()> {
         let mode_config = ModeConfigFile {
             modes: self.modes.borrow().values().cloned().collect(),
             order: self.mode_order.borrow().clone(),
+            tombstones: self.mode_tombstones.borrow().clone(),
         };
         let mode_config_json = serde_json::to_string(&mode_config)?;
-        cc_fs::write_string(DEFAULT_MODE_CONFIG_FILE_PATH, mode_config_json)
+        let path = Path::new(DEFAULT_MODE_CONFIG_FILE_PATH);
+        let bak_path = path.with_extension("json.bak");
+        if let Ok(previous_contents) = cc_fs::read_txt(path).await {
+            if let Err(err) = cc_fs::write_string(&bak_path, previous_contents).await {
+                warn!("Could not write Mode configuration backup {:?}: {}", bak_path, err);
+            }
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        let mut tmp_file = tokio::fs::File::create(&tmp_path)
             .await
-            .with_context(|| "Writing Modes Configuration File")?;
-        Ok(())
+            .with_context(|| format!("Creating temp Mode configuration file: {tmp_path:?}"))?;
+        tmp_file
+            .write_all(mode_config_json.as_bytes())
+            .await
+            .with_context(|| format!("Writing temp Mode configuration file: {tmp_path:?}"))?;
+        tmp_file
+            .sync_all()
+            .await
+            .with_context(|| format!("Fsyncing temp Mode configuration file: {tmp_path:?}"))?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .with_context(|| format!("Renaming temp Mode configuration file over: {path:?}"))
+    }
+
+    /// Reconciles the current Mode configuration with another `ModeConfigFile` read from
+    /// `other_path`, e.g. one pulled from another machine running CoolerControl or a restored
+    /// backup. See [`ModeConfigFile::merge`] for the precedence rules. The merged result replaces
+    /// the current state and is persisted via [`Self::save_modes_data`]; the returned conflicts
+    /// list every Mode whose settings diverged and were reconciled per-channel, for the user to
+    /// review.
+    pub async fn merge_mode_config_from_file(
+        &self,
+        other_path: &Path,
+    ) -> Result<Vec<ModeMergeConflict>> {
+        let other_contents = cc_fs::read_txt(other_path)
+            .await
+            .with_context(|| format!("Reading Mode configuration to merge: {other_path:?}"))?;
+        let other_config: ModeConfigFile = serde_json::from_str(&other_contents)
+            .with_context(|| format!("Parsing Mode configuration to merge: {other_path:?}"))?;
+        let mut current_config = ModeConfigFile {
+            modes: self.modes.borrow().values().cloned().collect(),
+            order: self.mode_order.borrow().clone(),
+            tombstones: self.mode_tombstones.borrow().clone(),
+        };
+        let conflicts = current_config.merge(&other_config);
+        {
+            let mut modes_lock = self.modes.borrow_mut();
+            modes_lock.clear();
+            for mode in current_config.modes {
+                modes_lock.insert(mode.uid.clone(), mode);
+            }
+        }
+        {
+            let mut mode_order_lock = self.mode_order.borrow_mut();
+            mode_order_lock.clear();
+            mode_order_lock.extend(current_config.order);
+        }
+        {
+            let mut tombstones_lock = self.mode_tombstones.borrow_mut();
+            tombstones_lock.clear();
+            tombstones_lock.extend(current_config.tombstones);
+        }
+        self.save_modes_data().await?;
+        Ok(conflicts)
+    }
+
+    /// Returns how many Mode channel settings currently reference `profile_uid`.
+    pub fn profile_reference_count(&self, profile_uid: &ProfileUID) -> usize {
+        self.search_for_deleted_profile(profile_uid).len()
+    }
+
+    /// Returns every `(mode_uid, device_uid, channel_name)` whose setting references
+    /// `profile_uid`.
+    pub fn modes_referencing(&self, profile_uid: &ProfileUID) -> Vec<(UID, DeviceUID, ChannelName)> {
+        self.search_for_deleted_profile(profile_uid)
     }
 
     /// Handles the deletion of a profile by removing references to it from other modes.
     ///
-    /// This function takes the UID of the deleted profile and removes any settings that reference
-    /// it from all modes.
+    /// Unless `force` is set, this refuses to touch anything while `profile_uid` is still
+    /// referenced by at least one Mode setting, returning a `CCError::UserError` describing the
+    /// referencing modes - a profile delete should never silently strip a carefully configured
+    /// Mode. With `force` set (or no references at all), this removes any settings that reference
+    /// the profile from all modes, exactly as before.
     ///
     /// # Parameters
     ///
     /// * `profile_uid`: The `ProfileUID` of the profile that was deleted.
+    /// * `force`: If `true`, strip referencing settings even though the profile is still in use.
     ///
     /// # Returns
     ///
     /// A `Result` containing `()`, indicating that the deletion was successful.
-    pub async fn profile_deleted(&self, profile_uid: &ProfileUID) -> Result<()> {
+    pub async fn profile_deleted(&self, profile_uid: &ProfileUID, force: bool) -> Result<()> {
         let settings_to_delete = self.search_for_deleted_profile(profile_uid);
+        if settings_to_delete.is_empty().not() && force.not() {
+            let modes = self.modes.borrow();
+            let mut referencing_mode_names: Vec<String> = settings_to_delete
+                .iter()
+                .map(|(mode_uid, _, _)| {
+                    modes
+                        .get(mode_uid)
+                        .map_or_else(|| mode_uid.clone(), |mode| mode.name.clone())
+                })
+                .collect();
+            referencing_mode_names.sort();
+            referencing_mode_names.dedup();
+            drop(modes);
+            return Err(CCError::UserError {
+                msg: format!(
+                    "Profile {profile_uid} is still referenced by {} mode setting(s), in: {referencing_mode_names:?}. \
+                     Pass force=true to delete the Profile and strip these settings anyway.",
+                    settings_to_delete.len()
+                ),
+            }
+            .into());
+        }
+        {
+            let mut modes = self.modes.borrow_mut();
+            for (mode_uid, _, _) in &settings_to_delete {
+                if let Some(mode) = modes.get_mut(mode_uid) {
+                    mode.version += 1;
+                }
+            }
+        }
         self.remove_affected_settings(settings_to_delete);
         self.save_modes_data().await?;
         Ok(())
@@ -649,15 +1300,178 @@ impl ModeController {
     }
 }
 
+/// Whether a Mode is a full device/channel snapshot or a small, layerable adjustment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ModeType {
+    /// The default: applying this Mode resets every channel not present in
+    /// `all_device_settings` back to its default, exactly like before this field existed.
+    #[default]
+    Full,
+
+    /// Applying this Mode only touches the channels explicitly listed in
+    /// `all_device_settings`; every other channel is left exactly as it is.
+    Overlay,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mode {
     pub uid: UID,
     pub name: String,
     pub all_device_settings: HashMap<UID, HashMap<ChannelName, Setting>>,
+
+    /// `Full` by default so Modes saved before this field existed keep their original behavior.
+    #[serde(default)]
+    pub mode_type: ModeType,
+
+    /// Other Modes this Mode inherits settings from, in declared (lowest-to-highest) precedence
+    /// order: a later parent's setting overrides an earlier parent's for the same channel. Only this (explicit) list and `all_device_settings` are ever persisted; the merged
+    /// view is computed on demand by [`ModeController::resolve_effective_settings`].
+    #[serde(default)]
+    pub parent_uids: Vec<UID>,
+
+    /// Named groups/tags this Mode belongs to, e.g. `"quiet-night"`, for organizing a large
+    /// number of Modes and for bulk activation via [`ModeController::resolve_group_settings`].
+    #[serde(default)]
+    pub groups: Vec<String>,
+
+    /// Monotonically increasing with every change to this Mode, bumped on every
+    /// [`ModeController`] mutator. Used by [`ModeConfigFile::merge`] to reconcile divergent
+    /// copies of the same Mode UID from different daemon instances or a restored backup.
+    #[serde(default)]
+    pub version: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ModeConfigFile {
     modes: Vec<Mode>,
     order: Vec<UID>,
+
+    /// UIDs of Modes deleted from this config. A tombstoned UID always wins over a copy of that
+    /// Mode resurrected by [`Self::merge`]-ing in another, older `ModeConfigFile`.
+    #[serde(default)]
+    tombstones: Vec<UID>,
+}
+
+/// A Mode whose settings diverged between the two sides of a [`ModeConfigFile::merge`] and were
+/// reconciled per-channel, for the user to review.
+#[derive(Debug, Clone)]
+pub struct ModeMergeConflict {
+    pub mode_uid: UID,
+    pub mode_name: String,
+    pub diverged_channels: Vec<(DeviceUID, ChannelName)>,
+}
+
+impl ModeConfigFile {
+    /// Merges `other` into `self`, following the CRDT-style semantics of Garage's `BlockRef`: a
+    /// `deleted` tombstone always wins over a Mode resurrected by the other side, the
+    /// higher-`version` copy of each remaining Mode wins, `order` is unioned (this config's order
+    /// first, then any new UIDs from `other` not already present), and when both sides have
+    /// touched the same Mode UID, their per-device/per-channel `Setting` maps are merged at the
+    /// finest granularity rather than one side's copy simply overwriting the other's. Returns a
+    /// report of every Mode whose settings diverged, so the user can review what was reconciled.
+    fn merge(&mut self, other: &ModeConfigFile) -> Vec<ModeMergeConflict> {
+        let mut tombstones = self.tombstones.clone();
+        for uid in &other.tombstones {
+            if tombstones.contains(uid).not() {
+                tombstones.push(uid.clone());
+            }
+        }
+
+        let mut self_modes: HashMap<UID, Mode> = self
+            .modes
+            .drain(..)
+            .map(|mode| (mode.uid.clone(), mode))
+            .collect();
+        let mut all_uids: Vec<UID> = self_modes.keys().cloned().collect();
+        for mode in &other.modes {
+            if all_uids.contains(&mode.uid).not() {
+                all_uids.push(mode.uid.clone());
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        let mut merged_modes: HashMap<UID, Mode> = HashMap::new();
+        for uid in &all_uids {
+            if tombstones.contains(uid) {
+                continue;
+            }
+            let mine = self_modes.remove(uid);
+            let theirs = other.modes.iter().find(|mode| &mode.uid == uid).cloned();
+            let merged = match (mine, theirs) {
+                (Some(mode), None) | (None, Some(mode)) => mode,
+                (Some(mine), Some(theirs)) => {
+                    let (merged, conflict) = Self::merge_mode(mine, theirs);
+                    if let Some(conflict) = conflict {
+                        conflicts.push(conflict);
+                    }
+                    merged
+                }
+                (None, None) => unreachable!("uid was collected from one of the two configs"),
+            };
+            merged_modes.insert(uid.clone(), merged);
+        }
+
+        let mut order: Vec<UID> = self
+            .order
+            .iter()
+            .filter(|uid| merged_modes.contains_key(*uid))
+            .cloned()
+            .collect();
+        for uid in &other.order {
+            if merged_modes.contains_key(uid) && order.contains(uid).not() {
+                order.push(uid.clone());
+            }
+        }
+        // Modes present in merged_modes but missing from both `order` vectors (shouldn't
+        // normally happen, but a divergent/hand-edited config file is exactly what `merge` has to
+        // tolerate) are still appended so they aren't silently dropped.
+        for uid in merged_modes.keys() {
+            if order.contains(uid).not() {
+                order.push(uid.clone());
+            }
+        }
+
+        self.modes = order
+            .iter()
+            .filter_map(|uid| merged_modes.remove(uid))
+            .collect();
+        self.order = order;
+        self.tombstones = tombstones;
+        conflicts
+    }
+
+    /// Reconciles two copies of the same Mode UID. The higher-`version` copy (ties go to `mine`)
+    /// wins for metadata (name/type/inheritance/groups), while `all_device_settings` is merged at
+    /// the per-device/per-channel level: a channel only one side touched is kept as-is, and a
+    /// channel both sides touched but disagree on takes the winning copy's value and is recorded
+    /// as a conflict.
+    fn merge_mode(mine: Mode, theirs: Mode) -> (Mode, Option<ModeMergeConflict>) {
+        let merged_version = mine.version.max(theirs.version);
+        let mine_wins = mine.version >= theirs.version;
+        let (mut winner, loser) = if mine_wins { (mine, theirs) } else { (theirs, mine) };
+
+        let mut diverged_channels = Vec::new();
+        let mut merged_settings = loser.all_device_settings;
+        for (device_uid, channel_settings) in &winner.all_device_settings {
+            let merged_channel_settings = merged_settings.entry(device_uid.clone()).or_default();
+            for (channel_name, setting) in channel_settings {
+                let diverged = merged_channel_settings
+                    .get(channel_name)
+                    .map_or(false, |existing| existing != setting);
+                if diverged {
+                    diverged_channels.push((device_uid.clone(), channel_name.clone()));
+                }
+                merged_channel_settings.insert(channel_name.clone(), setting.clone());
+            }
+        }
+
+        winner.all_device_settings = merged_settings;
+        winner.version = merged_version;
+        let conflict = diverged_channels.is_empty().not().then(|| ModeMergeConflict {
+            mode_uid: winner.uid.clone(),
+            mode_name: winner.name.clone(),
+            diverged_channels,
+        });
+        (winner, conflict)
+    }
 }
@@ -0,0 +1,52 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2021-2024  Guy Boldon, Eren Simsek and contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Helpers for skipping status reads on devices that are currently runtime-suspended (PCI D-state
+//! below D0), so polling doesn't force them back to full power just to take a reading.
+
+use std::path::{Path, PathBuf};
+
+const RUNTIME_STATUS_FILE: &str = "power/runtime_status";
+const RUNTIME_STATUS_SUSPENDED: &str = "suspended";
+const HWMON_CLASS_DIR: &str = "/sys/class/hwmon";
+
+/// Returns `true` if the sysfs device at `device_path` reports `power/runtime_status` as
+/// `suspended`. Devices that don't expose a `runtime_status` file (no runtime PM support) are
+/// treated as active, so they're never incorrectly skipped.
+pub async fn is_runtime_suspended(device_path: &Path) -> bool {
+    match tokio::fs::read_to_string(device_path.join(RUNTIME_STATUS_FILE)).await {
+        Ok(contents) => contents.trim() == RUNTIME_STATUS_SUSPENDED,
+        Err(_) => false,
+    }
+}
+
+/// Finds the sysfs hwmon device directory whose `name` file matches `chip_name`, e.g. to locate
+/// `/sys/class/hwmon/hwmon2/device` for `"k10temp"`. Returns `None` if no match is found.
+pub async fn find_hwmon_device_path_by_chip_name(chip_name: &str) -> Option<PathBuf> {
+    let mut hwmon_entries = tokio::fs::read_dir(HWMON_CLASS_DIR).await.ok()?;
+    while let Ok(Some(entry)) = hwmon_entries.next_entry().await {
+        let name_path = entry.path().join("name");
+        let Ok(name) = tokio::fs::read_to_string(&name_path).await else {
+            continue;
+        };
+        if name.trim() == chip_name {
+            return Some(entry.path().join("device"));
+        }
+    }
+    None
+}
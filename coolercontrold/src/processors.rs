@@ -0,0 +1,202 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2021-2024  Guy Boldon, Eren Simsek and contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use log::info;
+use tokio::sync::RwLock;
+
+use crate::config::Config;
+use crate::device::UID;
+use crate::setting::{interpolate_gradient_color, Function, FunctionType, LightingSettings, Profile};
+use crate::AllDevices;
+
+/// Applies each Profile's attached `Function` to smooth/debounce its raw temperature reading
+/// before the `Profile`'s `speed_profile` is evaluated, and handles the daemon-wide control
+/// operations that don't belong to any single repository (ThinkPad fan control, etc).
+pub struct SettingsProcessor {
+    #[allow(dead_code)]
+    all_devices: AllDevices,
+    config: Arc<Config>,
+    function_states: RwLock<HashMap<(UID, String), FunctionState>>,
+    lighting_states: RwLock<HashMap<(UID, String), (u8, u8, u8)>>,
+}
+
+/// Per-(profile, temp_source) state carried between polls for a `Function`. Which variant is
+/// populated depends on the `Function`'s `f_type`; `Identity` needs none.
+enum FunctionState {
+    Standard {
+        last_committed: f64,
+        pending: Option<(f64, Instant)>,
+    },
+    SimpleMovingAvg(VecDeque<f64>),
+    ExponentialMovingAvg(f64),
+}
+
+impl SettingsProcessor {
+    pub fn new(all_devices: AllDevices, config: Arc<Config>) -> Self {
+        Self {
+            all_devices,
+            config,
+            function_states: RwLock::new(HashMap::new()),
+            lighting_states: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Transforms `raw_temp` according to the `Function` attached to `profile`, maintaining
+    /// whatever smoothing/debounce state that `Function` type needs between calls. Keyed by
+    /// profile uid + temp source name, so two profiles (or two temp sources) never share state.
+    pub async fn process_temp(&self, profile: &Profile, function: &Function, raw_temp: f64) -> f64 {
+        let Some(temp_source) = &profile.temp_source else {
+            return raw_temp;
+        };
+        let key = (profile.uid.clone(), temp_source.temp_name.clone());
+        let mut states = self.function_states.write().await;
+        match function.f_type {
+            FunctionType::Identity => raw_temp,
+            FunctionType::SimpleMovingAvg => {
+                let window_size = function.sample_window.unwrap_or(1).max(1) as usize;
+                let buffer = match states
+                    .entry(key)
+                    .or_insert_with(|| FunctionState::SimpleMovingAvg(VecDeque::with_capacity(window_size)))
+                {
+                    FunctionState::SimpleMovingAvg(buffer) => buffer,
+                    _ => unreachable!("key is exclusive to one Function type at a time"),
+                };
+                buffer.push_back(raw_temp);
+                while buffer.len() > window_size {
+                    buffer.pop_front();
+                }
+                buffer.iter().sum::<f64>() / buffer.len() as f64
+            }
+            FunctionType::ExponentialMovingAvg => {
+                let window_size = function.sample_window.unwrap_or(1).max(1) as f64;
+                let alpha = 2.0 / (window_size + 1.0);
+                let ema = match states
+                    .entry(key)
+                    .or_insert_with(|| FunctionState::ExponentialMovingAvg(raw_temp))
+                {
+                    FunctionState::ExponentialMovingAvg(ema) => ema,
+                    _ => unreachable!("key is exclusive to one Function type at a time"),
+                };
+                *ema = alpha * raw_temp + (1.0 - alpha) * *ema;
+                *ema
+            }
+            FunctionType::Standard => {
+                let deviance = function.deviance.unwrap_or(0.0);
+                let response_delay = function.response_delay.unwrap_or(0);
+                let state = match states.entry(key).or_insert_with(|| FunctionState::Standard {
+                    last_committed: raw_temp,
+                    pending: None,
+                }) {
+                    FunctionState::Standard { last_committed, pending } => (last_committed, pending),
+                    _ => unreachable!("key is exclusive to one Function type at a time"),
+                };
+                let (last_committed, pending) = state;
+                if (raw_temp - *last_committed).abs() <= deviance {
+                    // back within the deadband: cancel any pending change and hold.
+                    *pending = None;
+                    return *last_committed;
+                }
+                let now = Instant::now();
+                match pending {
+                    Some((pending_value, since)) if (raw_temp - *pending_value).abs() <= deviance => {
+                        if since.elapsed().as_secs() >= u64::from(response_delay) {
+                            *last_committed = *pending_value;
+                            *pending = None;
+                        }
+                    }
+                    _ => {
+                        *pending = Some((raw_temp, now));
+                    }
+                }
+                *last_committed
+            }
+        }
+    }
+
+    /// Recomputes the thermometer gradient color for a `temp_reactive` lighting channel given
+    /// its current smoothed temperature, returning the new RGB color to push down the
+    /// liquidctl color-channel apply path only if it actually changed since the last call - a
+    /// steady temperature shouldn't re-send the same color on every poll cycle.
+    pub async fn reapply_temp_reactive_lighting(
+        &self,
+        device_uid: &UID,
+        channel_name: &str,
+        lighting: &LightingSettings,
+        smoothed_temp: f64,
+    ) -> Option<(u8, u8, u8)> {
+        let (Some(cold), Some(hot), Some(temp_min), Some(temp_max)) = (
+            lighting.gradient_cold_color,
+            lighting.gradient_hot_color,
+            lighting.temp_min,
+            lighting.temp_max,
+        ) else {
+            return None;
+        };
+        let color = interpolate_gradient_color(cold, hot, smoothed_temp, temp_min, temp_max);
+        let key = (device_uid.clone(), channel_name.to_string());
+        let mut states = self.lighting_states.write().await;
+        if states.get(&key) == Some(&color) {
+            return None;
+        }
+        states.insert(key, color);
+        Some(color)
+    }
+
+    /// Clears smoothing state for every profile that references `function_uid`, so edits to a
+    /// Function's `sample_window`/`deviance`/`response_delay` take effect immediately instead of
+    /// blending with readings taken under the old settings.
+    pub async fn function_updated(&self, function_uid: &str) {
+        self.clear_states_for_function(function_uid).await;
+    }
+
+    /// Same as `function_updated`: a deleted Function can no longer be meaningfully resumed, so
+    /// any state built up under it should be dropped.
+    pub async fn function_deleted(&self, function_uid: &str) {
+        self.clear_states_for_function(function_uid).await;
+    }
+
+    async fn clear_states_for_function(&self, function_uid: &str) {
+        let Ok(profiles) = self.config.get_profiles().await else {
+            return;
+        };
+        let affected_profile_uids: Vec<UID> = profiles
+            .into_iter()
+            .filter(|profile| profile.function_uid == function_uid)
+            .map(|profile| profile.uid)
+            .collect();
+        if affected_profile_uids.is_empty() {
+            return;
+        }
+        let mut states = self.function_states.write().await;
+        states.retain(|(profile_uid, _), _| !affected_profile_uids.contains(profile_uid));
+    }
+
+    /// Enables or disables ThinkPad full-speed fan control.
+    pub async fn thinkpad_fan_control(&self, enable: &bool) -> Result<()> {
+        info!("Setting ThinkPad full-speed fan control: {enable}");
+        let mut settings = self.config.get_settings().await?;
+        settings.thinkpad_full_speed = *enable;
+        self.config.set_settings(&settings).await;
+        self.config.save_config_file().await
+    }
+}
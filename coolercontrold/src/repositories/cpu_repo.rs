@@ -16,140 +16,470 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  ******************************************************************************/
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use log::{debug, error, info};
 use psutil::cpu::CpuPercentCollector;
-use psutil::sensors::TemperatureSensor;
-use tokio::process::Command;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tokio::time::Instant;
-use zbus::export::futures_util::future::join_all;
 
+use crate::cc_fs;
 use crate::device::{ChannelStatus, Device, DeviceInfo, DeviceType, Status, TempStatus, UID};
+use crate::power_state;
 use crate::repositories::repository::{DeviceList, Repository};
 use crate::setting::Setting;
 
 const CPU_TEMP_NAME: &str = "CPU Temp";
 const CPU_LOAD_NAME: &str = "CPU Load";
-pub const PSUTIL_CPU_SENSOR_NAMES: [&'static str; 4] =
-    ["thinkpad", "k10temp", "coretemp", "zenpower"];
-const PSUTIL_CPU_SENSOR_LABELS: [&'static str; 6] =
-    ["CPU", "tctl", "physical", "package", "tdie", ""];
+const CPU_FREQ_NAME: &str = "CPU Freq";
+const HWMON_CLASS_DIR: &str = "/sys/class/hwmon";
+const THERMAL_CLASS_DIR: &str = "/sys/class/thermal";
+const CPU_SYSFS_DIR: &str = "/sys/devices/system/cpu";
+/// Chip names known to report CPU package temperatures, preferred over any other hwmon chip
+/// present (NVMe drives, Wi-Fi cards, etc.) when deciding which readings are "the CPU".
+const KNOWN_CPU_CHIP_NAMES: [&str; 4] = ["thinkpad", "k10temp", "coretemp", "zenpower"];
+/// Labels that indicate a package/die-wide sensor rather than a single core, scored highest so
+/// they're preferred over noisier per-core readings. Order doesn't matter here; every match gets
+/// the same top score.
+const PACKAGE_LABEL_HINTS: [&str; 6] = ["tctl", "tdie", "package", "physical", "die", "cpu"];
+
+/// A single `tempN_*` sysfs node group read from a hwmon chip directory, in Celsius.
+#[derive(Debug, Clone)]
+struct HwmonTempReading {
+    label: Option<String>,
+    celsius: f64,
+    max_celsius: Option<f64>,
+    crit_celsius: Option<f64>,
+}
+
+/// A hwmon chip directory (`/sys/class/hwmon/hwmonN`) along with every temperature it reports.
+#[derive(Debug, Clone)]
+struct HwmonChip {
+    name: String,
+    path: PathBuf,
+    /// The chip's `device` symlink, canonicalized, identifying the physical package it belongs
+    /// to independent of hwmon's (re)enumeration order across reboots. `None` when the chip has
+    /// no `device` symlink (virtual/software chips).
+    device_path: Option<PathBuf>,
+    temps: Vec<HwmonTempReading>,
+}
+
+/// A user override for which hwmon chip and temperature label CoolerControl selects as the CPU
+/// temperature, for boards where `KNOWN_CPU_CHIP_NAMES`/`PACKAGE_LABEL_HINTS` pick the wrong
+/// sensor (e.g. a "Tctl" reading that doesn't track the package on some motherboards). `chip`
+/// and `label` are matched against `sanitize_label` output; an empty pattern matches anything.
+/// Preferences are tried in list order against every discovered candidate - the first entry to
+/// match any candidate wins outright, ahead of the built-in scoring.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuSensorPreference {
+    /// Substring or regex pattern matched against the chip's sanitized `name`.
+    #[serde(default)]
+    pub chip: String,
+    /// Substring or regex pattern matched against a reading's sanitized `label`.
+    #[serde(default)]
+    pub label: String,
+    /// Treat `chip`/`label` as regular expressions instead of literal substrings.
+    #[serde(default)]
+    pub regex: bool,
+}
+
+impl CpuSensorPreference {
+    /// Whether this preference matches a given chip name / label pair, both already run through
+    /// `sanitize_label`.
+    fn matches(&self, chip_name: &str, label: &str) -> bool {
+        Self::pattern_matches(&self.chip, chip_name, self.regex)
+            && Self::pattern_matches(&self.label, label, self.regex)
+    }
+
+    fn pattern_matches(pattern: &str, value: &str, regex: bool) -> bool {
+        if pattern.is_empty() {
+            return true;
+        }
+        let body = if regex { pattern.to_string() } else { regex::escape(pattern) };
+        Regex::new(&format!("(?i){body}")).map(|re| re.is_match(value)).unwrap_or(false)
+    }
+}
 
 /// A CPU Repository for CPU status
 pub struct CpuRepo {
     devices: DeviceList,
     cpu_collector: RwLock<CpuPercentCollector>,
-    current_sensor_name: RwLock<Option<String>>,
-    current_label_name: RwLock<Option<String>>,
+    /// The resolved physical device path last used to find a temperature reading, per CPU
+    /// socket (`type_index`), so `is_runtime_suspended` can check the right device path without
+    /// re-resolving it from a chip name (which, unlike the device path, isn't unique per socket).
+    current_device_paths: RwLock<HashMap<u8, PathBuf>>,
     preloaded_statuses: RwLock<HashMap<u8, (Vec<ChannelStatus>, Vec<TempStatus>)>>,
+    /// When disabled, skips the runtime-suspend check below and always takes a fresh reading.
+    /// Mirrors `CoolerControlSettings::power_aware_polling`; set via `set_power_aware_polling`.
+    power_aware_polling: RwLock<bool>,
+    /// User-configured overrides for which hwmon chip/label is "the CPU", tried in order ahead
+    /// of the built-in `KNOWN_CPU_CHIP_NAMES`/`PACKAGE_LABEL_HINTS` detection.
+    sensor_preferences: Vec<CpuSensorPreference>,
+    /// The selected temperature sensor's reported `tempN_max`/`tempN_crit` (in Celsius), per CPU
+    /// socket, used to populate `DeviceInfo::temp_max`/`temp_crit` instead of a hardcoded guess.
+    socket_temp_limits: RwLock<HashMap<u8, (Option<f64>, Option<f64>)>>,
 }
 
 impl CpuRepo {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(sensor_preferences: Vec<CpuSensorPreference>) -> Result<Self> {
         Ok(Self {
             devices: vec![],
             cpu_collector: RwLock::new(CpuPercentCollector::new()?),
-            current_sensor_name: RwLock::new(None),
-            current_label_name: RwLock::new(None),
+            current_device_paths: RwLock::new(HashMap::new()),
             preloaded_statuses: RwLock::new(HashMap::new()),
+            power_aware_polling: RwLock::new(true),
+            sensor_preferences,
+            socket_temp_limits: RwLock::new(HashMap::new()),
         })
     }
 
-    async fn request_status(&self) -> Result<(Vec<ChannelStatus>, Vec<TempStatus>)> {
-        let mut temp_sensors = vec![];
-        for sensor_result in psutil::sensors::temperatures() {
-            if let Ok(sensor) = sensor_result {
-                temp_sensors.push(sensor)
-            }
+    /// Mirrors `CoolerControlSettings::power_aware_polling`: when disabled, status reads always
+    /// touch the hardware, even if the CPU's hwmon chip reports as runtime-suspended.
+    pub async fn set_power_aware_polling(&self, enabled: bool) {
+        *self.power_aware_polling.write().await = enabled;
+    }
+
+    /// Checks whether a physical socket's currently detected hwmon chip is runtime-suspended, to
+    /// avoid waking it out of D3 just to take a reading that will be stale within a second
+    /// anyway.
+    async fn is_runtime_suspended(&self, type_index: u8) -> bool {
+        if !*self.power_aware_polling.read().await {
+            return false;
         }
-        // let physical_cpu_count = psutil::cpu::cpu_count_physical();
-        if self.current_sensor_name.read().await.is_none() {
+        let Some(device_path) = self.current_device_paths.read().await.get(&type_index).cloned() else {
+            return false;
+        };
+        power_state::is_runtime_suspended(&device_path).await
+    }
+
+    /// Discovers every physical socket's CPU status, keyed by `type_index` (1-based, in
+    /// discovery order).
+    async fn request_statuses(&self) -> Result<HashMap<u8, (Vec<ChannelStatus>, Vec<TempStatus>)>> {
+        let chips = Self::discover_hwmon_chips().await;
+        if self.current_device_paths.read().await.is_empty() {
             // only log all responses the first time
-            debug!("Detected temperature sensors: {:?}", temp_sensors);
+            debug!("Detected hwmon chips: {:?}", chips);
+        }
+        self.discover_socket_statuses(chips).await
+    }
+
+    /// Walks every `/sys/class/hwmon/hwmonN` directory, reading its `name` file and every
+    /// `tempN_input`/`tempN_label`/`tempN_max`/`tempN_crit` node it exposes. Chips with no
+    /// temperature readings at all are skipped. Replaces the psutil-based sensor scan, which
+    /// silently failed on any chip psutil's hwmon backend doesn't already know how to label.
+    async fn discover_hwmon_chips() -> Vec<HwmonChip> {
+        let mut chips = vec![];
+        let Ok(mut hwmon_entries) = tokio::fs::read_dir(HWMON_CLASS_DIR).await else {
+            return chips;
+        };
+        while let Ok(Some(entry)) = hwmon_entries.next_entry().await {
+            let path = entry.path();
+            let Ok(name) = tokio::fs::read_to_string(path.join("name")).await else {
+                continue;
+            };
+            let temps = Self::read_hwmon_chip_temps(&path).await;
+            if temps.is_empty() {
+                continue;
+            }
+            let device_path = tokio::fs::canonicalize(path.join("device")).await.ok();
+            chips.push(HwmonChip { name: name.trim().to_string(), path, device_path, temps });
         }
-        self.request_statuses_new(temp_sensors).await
+        chips
     }
 
-    /// This is used to find the correct sensors and labels for cpu data.
-    async fn request_statuses_new(
+    /// Reads every `tempN_input` sysfs node (plus its `_label`/`_max`/`_crit` companions, when
+    /// present) under a single hwmon chip directory. Readings are converted from millidegrees
+    /// Celsius to Celsius.
+    async fn read_hwmon_chip_temps(chip_dir: &Path) -> Vec<HwmonTempReading> {
+        let Ok(mut entries) = tokio::fs::read_dir(chip_dir).await else {
+            return vec![];
+        };
+        let mut indices = vec![];
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if let Some(file_name) = entry.file_name().to_str() {
+                if let Some(index) = file_name.strip_prefix("temp").and_then(|rest| rest.strip_suffix("_input")) {
+                    indices.push(index.to_string());
+                }
+            }
+        }
+        let mut readings = vec![];
+        for index in indices {
+            let Some(celsius) = Self::read_millic(chip_dir, &format!("temp{index}_input")).await else {
+                continue;
+            };
+            let label = tokio::fs::read_to_string(chip_dir.join(format!("temp{index}_label"))).await
+                .ok()
+                .map(|label| label.trim().to_string());
+            let max_celsius = Self::read_millic(chip_dir, &format!("temp{index}_max")).await;
+            let crit_celsius = Self::read_millic(chip_dir, &format!("temp{index}_crit")).await;
+            readings.push(HwmonTempReading { label, celsius, max_celsius, crit_celsius });
+        }
+        readings
+    }
+
+    /// Reads a hwmon millidegree-Celsius sysfs node and converts it to Celsius.
+    async fn read_millic(dir: &Path, file_name: &str) -> Option<f64> {
+        let contents = tokio::fs::read_to_string(dir.join(file_name)).await.ok()?;
+        Some(contents.trim().parse::<f64>().ok()? / 1000.0)
+    }
+
+    /// Scans `/sys/class/thermal/thermal_zone*/type` for a zone whose type names the CPU
+    /// package (`x86_pkg_temp` on Intel, or any type containing `"cpu"`), used only when no
+    /// known CPU hwmon chip was found.
+    async fn fallback_thermal_zone_temp() -> Option<f64> {
+        let mut entries = tokio::fs::read_dir(THERMAL_CLASS_DIR).await.ok()?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(zone_type) = tokio::fs::read_to_string(entry.path().join("type")).await else {
+                continue;
+            };
+            let zone_type = zone_type.trim().to_lowercase();
+            if zone_type.contains("x86_pkg_temp") || zone_type.contains("cpu") {
+                if let Some(celsius) = Self::read_millic(&entry.path(), "temp").await {
+                    return Some(celsius);
+                }
+            }
+        }
+        None
+    }
+
+    /// Reads the current scaling frequency (in MHz) of every online logical core from
+    /// `/sys/devices/system/cpu/cpu*/cpufreq/scaling_cur_freq` (reported in kHz) and returns the
+    /// max across them, as a simple single-number signal to correlate against load and
+    /// temperature. Returns `None` when cpufreq isn't present at all (e.g. in a VM), rather than
+    /// reporting a misleading 0 MHz.
+    async fn read_cpu_freq_mhz() -> Option<f64> {
+        let mut entries = tokio::fs::read_dir(CPU_SYSFS_DIR).await.ok()?;
+        let mut max_khz: Option<u64> = None;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let file_name = entry.file_name();
+            let Some(cpu_dir) = file_name.to_str() else {
+                continue;
+            };
+            if !cpu_dir.starts_with("cpu") || !cpu_dir[3..].chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            let freq_path = entry.path().join("cpufreq").join("scaling_cur_freq");
+            let Ok(contents) = tokio::fs::read_to_string(&freq_path).await else {
+                continue;
+            };
+            let Ok(khz) = contents.trim().parse::<u64>() else {
+                continue;
+            };
+            max_khz = Some(max_khz.map_or(khz, |m| m.max(khz)));
+        }
+        max_khz.map(|khz| khz as f64 / 1000.0)
+    }
+
+    /// Buckets the known-CPU hwmon chips by their resolved physical device path - one CPU socket
+    /// per distinct package, rather than one per distinct chip name, so boards where a single
+    /// package's sensors span multiple hwmon chips (or a future kernel splits what used to be
+    /// one) don't get merged into, or split out of, the wrong socket. Scores each socket's
+    /// candidates - preferring package/tctl/tdie-style "die" labels - and falls back to the max
+    /// reading across per-core sensors when no such package-wide sensor exists. When no known
+    /// CPU chip was found at all, falls back to a thermal zone reading.
+    async fn discover_socket_statuses(
         &self,
-        temp_sensors: Vec<TemperatureSensor>,
-    ) -> Result<(Vec<ChannelStatus>, Vec<TempStatus>)> {
-        for cpu_sensor_name in PSUTIL_CPU_SENSOR_NAMES {  // order is important
-            for temp_sensor in &temp_sensors {
-                if temp_sensor.unit() == cpu_sensor_name {
-                    if let Some(sensor_label) = temp_sensor.label() {
-                        let label = Self::sanitize_label(sensor_label);
-                        for cpu_label in PSUTIL_CPU_SENSOR_LABELS {  // order is important
-                            if label.contains(cpu_label) {
-                                self.set_current_sensor_names(cpu_sensor_name, &label).await;
-                                let cpu_usage = self.cpu_collector.write().await.cpu_percent()?;
-                                return Ok((
-                                    vec![ChannelStatus {
-                                        name: CPU_LOAD_NAME.to_string(),
-                                        rpm: None,
-                                        duty: Some(cpu_usage as f64),
-                                        pwm_mode: None,
-                                    }],
-                                    vec![TempStatus {
-                                        name: CPU_TEMP_NAME.to_string(),
-                                        temp: temp_sensor.current().celsius(),
-                                        frontend_name: CPU_TEMP_NAME.to_string(),
-                                        external_name: CPU_TEMP_NAME.to_string(),
-                                    }],
-                                ));
-                            }
-                        }
-                    }
+        chips: Vec<HwmonChip>,
+    ) -> Result<HashMap<u8, (Vec<ChannelStatus>, Vec<TempStatus>)>> {
+        let candidates: Vec<&HwmonChip> = self.preferred_chips(&chips).unwrap_or_else(|| {
+            chips.iter()
+                .filter(|chip| KNOWN_CPU_CHIP_NAMES.contains(&chip.name.as_str()))
+                .collect()
+        });
+        let cpu_usage = self.cpu_collector.write().await.cpu_percent()?;
+        let cpu_freq_mhz = Self::read_cpu_freq_mhz().await;
+        let mut statuses = HashMap::new();
+        let mut type_index = 1u8;
+        if candidates.is_empty() {
+            if let Some(temp) = Self::fallback_thermal_zone_temp().await {
+                statuses.insert(type_index, Self::socket_status(cpu_usage as f64, cpu_freq_mhz, temp, vec![]));
+                type_index += 1;
+            }
+        } else {
+            // A Vec rather than a HashMap, so sockets keep the order their first chip was found in.
+            let mut by_device: Vec<(Option<PathBuf>, Vec<&HwmonChip>)> = Vec::new();
+            for chip in candidates {
+                match by_device.iter_mut().find(|(path, _)| *path == chip.device_path) {
+                    Some((_, socket_chips)) => socket_chips.push(chip),
+                    None => by_device.push((chip.device_path.clone(), vec![chip])),
+                }
+            }
+            for (_, socket_chips) in by_device {
+                let Some((temp, max, crit)) = Self::best_temp_for_chips(&socket_chips) else {
+                    continue;
+                };
+                let cores = socket_chips.iter()
+                    .flat_map(|chip| Self::core_temp_statuses(chip, type_index))
+                    .collect();
+                if let Some(device_path) = socket_chips[0].device_path.clone() {
+                    self.current_device_paths.write().await.insert(type_index, device_path);
                 }
+                self.socket_temp_limits.write().await.insert(type_index, (max, crit));
+                statuses.insert(type_index, Self::socket_status(cpu_usage as f64, cpu_freq_mhz, temp, cores));
+                type_index += 1;
             }
         }
-        Err(anyhow!("No CPU Temperatures found: {:?}", temp_sensors))
+        if statuses.is_empty() {
+            return Err(anyhow!("No CPU Temperatures found"));
+        }
+        Ok(statuses)
     }
 
-    fn sanitize_label(sensor_label: &str) -> String {
-        sensor_label.to_lowercase().replace(" ", "_")
+    /// Applies `sensor_preferences` in order, returning the first preference's matching chips
+    /// (logged as the detected-vs-selected sensor), or `None` if no preference is configured or
+    /// none of them match anything, so the caller falls back to the built-in detection.
+    fn preferred_chips<'a>(&self, chips: &'a [HwmonChip]) -> Option<Vec<&'a HwmonChip>> {
+        for preference in &self.sensor_preferences {
+            let matches: Vec<&HwmonChip> = chips.iter()
+                .filter(|chip| {
+                    let chip_name = Self::sanitize_label(&chip.name);
+                    chip.temps.iter().any(|reading| {
+                        let label = Self::sanitize_label(&reading.label.clone().unwrap_or_default());
+                        preference.matches(&chip_name, &label)
+                    })
+                })
+                .collect();
+            if !matches.is_empty() {
+                debug!(
+                    "CPU sensor preference {:?} matched hwmon chip(s): {:?}",
+                    preference,
+                    matches.iter().map(|chip| &chip.name).collect::<Vec<_>>()
+                );
+                return Some(matches);
+            }
+        }
+        if !self.sensor_preferences.is_empty() {
+            debug!("No hwmon chip matched the configured CPU sensor preferences; falling back to built-in detection");
+        }
+        None
+    }
+
+    fn socket_status(
+        cpu_usage: f64,
+        cpu_freq_mhz: Option<f64>,
+        temp: f64,
+        mut core_temps: Vec<TempStatus>,
+    ) -> (Vec<ChannelStatus>, Vec<TempStatus>) {
+        let mut temps = vec![TempStatus {
+            name: CPU_TEMP_NAME.to_string(),
+            temp,
+            frontend_name: CPU_TEMP_NAME.to_string(),
+            external_name: CPU_TEMP_NAME.to_string(),
+        }];
+        temps.append(&mut core_temps);
+        let mut channels = vec![ChannelStatus {
+            name: CPU_LOAD_NAME.to_string(),
+            rpm: None,
+            duty: Some(cpu_usage),
+            pwm_mode: None,
+        }];
+        if let Some(freq_mhz) = cpu_freq_mhz {
+            channels.push(ChannelStatus {
+                name: CPU_FREQ_NAME.to_string(),
+                rpm: Some(freq_mhz.round() as u32),
+                duty: None,
+                pwm_mode: None,
+            });
+        }
+        (channels, temps)
     }
 
-    async fn set_current_sensor_names(&self, cpu_sensor_name: &str, label: &String) {
-        self.current_sensor_name.write().await
-            .replace(cpu_sensor_name.to_string());
-        self.current_label_name.write().await
-            .replace(label.clone());
+    /// Surfaces every per-core/per-die reading on a chip (labels matching `Core N`, `Tctl`,
+    /// `Tdie`, or `TccdN`) as its own `TempStatus`, alongside the aggregate package temperature
+    /// already picked as `CPU Temp`, so per-core and per-CCD boards show every temperature the
+    /// chip reports instead of only the synthesized package aggregate.
+    fn core_temp_statuses(chip: &HwmonChip, device_id: u8) -> Vec<TempStatus> {
+        let mut seen = HashSet::new();
+        let mut temps = vec![];
+        for reading in &chip.temps {
+            let Some(label) = &reading.label else {
+                continue;
+            };
+            let sanitized = Self::sanitize_label(label);
+            let is_core_or_die = sanitized.contains("core")
+                || sanitized.contains("tctl")
+                || sanitized.contains("tdie")
+                || sanitized.contains("ccd");
+            if !is_core_or_die || !seen.insert(sanitized.clone()) {
+                continue;
+            }
+            temps.push(TempStatus {
+                name: sanitized,
+                temp: reading.celsius,
+                frontend_name: label.clone(),
+                external_name: format!("CPU#{device_id} {label}"),
+            });
+        }
+        temps
     }
 
+    /// Prefers the max reading across any package/die-wide sensors (scored by
+    /// `PACKAGE_LABEL_HINTS`); falls back to the max across whatever per-core sensors these
+    /// chips report when none has a package-wide sensor. Readings are deduped by
+    /// `(chip name, label)` so a sensor exposed twice under the same chip isn't double-counted.
+    /// Returns the winning reading's temperature alongside its `tempN_max`/`tempN_crit`
+    /// companions (in Celsius), when the chip reported them, for thermal-ceiling reporting.
+    fn best_temp_for_chips(chips: &[&HwmonChip]) -> Option<(f64, Option<f64>, Option<f64>)> {
+        let mut seen = HashSet::new();
+        let mut best_package: Option<&HwmonTempReading> = None;
+        let mut max_any: Option<&HwmonTempReading> = None;
+        for chip in chips {
+            for reading in &chip.temps {
+                let label = reading.label.clone().unwrap_or_default();
+                if !seen.insert((chip.name.clone(), label.clone())) {
+                    continue;
+                }
+                max_any = Some(max_any.map_or(reading, |m| if reading.celsius > m.celsius { reading } else { m }));
+                let sanitized = Self::sanitize_label(&label);
+                if PACKAGE_LABEL_HINTS.iter().any(|hint| sanitized.contains(hint)) {
+                    best_package = Some(best_package.map_or(reading, |b| if reading.celsius > b.celsius { reading } else { b }));
+                }
+            }
+        }
+        best_package.or(max_any).map(|reading| (reading.celsius, reading.max_celsius, reading.crit_celsius))
+    }
+
+    fn sanitize_label(sensor_label: &str) -> String {
+        sensor_label.to_lowercase().replace(' ', "_")
+    }
+
+    /// Parses the CPU model name straight out of `/proc/cpuinfo`, avoiding the `lscpu` shell
+    /// dependency, which may not be installed. Falls back to the detected hwmon chip name, then
+    /// the literal `"cpu"`, if `/proc/cpuinfo` doesn't have a `model name` line.
     async fn get_cpu_name(&self) -> String {
-        let output = Command::new("sh")
-            .arg("-c")
-            .arg("LC_ALL=C lscpu")
-            .output().await;
-        match output {
-            Ok(out) => {
-                if out.status.success() {
-                    let out_str = String::from_utf8(out.stdout).unwrap();
-                    for line in out_str.trim().lines() {
-                        if line.to_lowercase().contains("model name") {
-                            let parts = line.split(":").collect::<Vec<&str>>();
-                            if parts.len() > 1 {
-                                return parts[1].trim().to_string();
-                            }
+        match cc_fs::read_txt("/proc/cpuinfo").await {
+            Ok(contents) => {
+                for line in contents.lines() {
+                    if line.to_lowercase().starts_with("model name") {
+                        if let Some((_, value)) = line.split_once(':') {
+                            return value.trim().to_string();
                         }
                     }
-                    error!("Looking up CPU name returned unexpected response:\n{}", out_str)
-                } else {
-                    let out_err = String::from_utf8(out.stderr).unwrap();
-                    error!("Error looking up CPU name: {}", out_err)
                 }
+                error!("No 'model name' line found in /proc/cpuinfo");
             }
-            Err(err) => error!("Error looking up CPU name: {}", err)
+            Err(err) => error!("Error reading /proc/cpuinfo: {}", err),
+        }
+        if let Some(chip_name) = Self::known_cpu_chip_name().await {
+            return chip_name;
         }
         "cpu".to_string()
     }
+
+    /// Falls back to the first known-CPU hwmon chip's name (e.g. `"k10temp"`) when
+    /// `/proc/cpuinfo` doesn't yield a model name, so the device is still labeled with something
+    /// more specific than the literal `"cpu"`.
+    async fn known_cpu_chip_name() -> Option<String> {
+        Self::discover_hwmon_chips().await.into_iter()
+            .find(|chip| KNOWN_CPU_CHIP_NAMES.contains(&chip.name.as_str()))
+            .map(|chip| chip.name)
+    }
 }
 
 #[async_trait]
@@ -159,36 +489,47 @@ impl Repository for CpuRepo {
     }
 
     async fn initialize_devices(&mut self) -> Result<()> {
-        // todo: handle multiple cpus
-        //   To do this correctly, I see we just get more Tctl temperatures from the system, but
-        //   to really properly track wich cpu socket belongs to which temp we need to handle
-        //   the hwmon files ourselves. (device path aka UID)
         debug!("Starting Device Initialization");
-        let type_index = 1u8;
         let start_initialization = Instant::now();
-        let (channels, temps) = self.request_status().await?;
-        self.preloaded_statuses.write().await
-            .insert(type_index, (channels.clone(), temps.clone()));
-        let status = Status {
-            channels,
-            temps,
-            ..Default::default()
-        };
+        let statuses = self.request_statuses().await?;
         let cpu_name = self.get_cpu_name().await;
-        let device = Device::new(
-            cpu_name,
-            DeviceType::CPU,
-            type_index,
-            None,
-            Some(DeviceInfo {
-                temp_max: 100,
-                temp_ext_available: true,
+        let mut type_indices: Vec<u8> = statuses.keys().copied().collect();
+        type_indices.sort_unstable();
+        for type_index in type_indices {
+            let (channels, temps) = statuses[&type_index].clone();
+            self.preloaded_statuses.write().await
+                .insert(type_index, (channels.clone(), temps.clone()));
+            let status = Status {
+                channels,
+                temps,
                 ..Default::default()
-            }),
-            Some(status),
-            None,  // use default
-        );
-        self.devices.push(Arc::new(RwLock::new(device)));
+            };
+            // Only disambiguate socket names once a second socket is actually detected.
+            let device_name = if statuses.len() > 1 {
+                format!("{cpu_name} #{type_index}")
+            } else {
+                cpu_name.clone()
+            };
+            let (max, crit) = self.socket_temp_limits.read().await
+                .get(&type_index)
+                .copied()
+                .unwrap_or((None, None));
+            let device = Device::new(
+                device_name,
+                DeviceType::CPU,
+                type_index,
+                None,
+                Some(DeviceInfo {
+                    temp_max: max.map(|celsius| celsius.round() as u8).unwrap_or(100),
+                    temp_crit: crit.map(|celsius| celsius.round() as u8),
+                    temp_ext_available: true,
+                    ..Default::default()
+                }),
+                Some(status),
+                None,  // use default
+            );
+            self.devices.push(Arc::new(RwLock::new(device)));
+        }
         let mut init_devices = vec![];
         for device in self.devices.iter() {
             init_devices.push(device.read().await.clone())
@@ -211,21 +552,31 @@ impl Repository for CpuRepo {
 
     async fn preload_statuses(&self) {
         let start_update = Instant::now();
-        let mut futures = Vec::new();
+        let mut suspended_count = 0usize;
         for device_lock in &self.devices {
-            futures.push(
-                async {
-                    let status = self.request_status().await;
-                    if let Err(err) = status {
-                        error!("Error getting CPU status: {}", err);
-                        return;
+            let type_index = device_lock.read().await.type_index;
+            if self.is_runtime_suspended(type_index).await {
+                suspended_count += 1;
+            }
+        }
+        if !self.devices.is_empty() && suspended_count == self.devices.len() {
+            debug!("All CPU sensor chips are runtime-suspended, reusing last cached status");
+            return;
+        }
+        match self.request_statuses().await {
+            Ok(statuses) => {
+                for device_lock in &self.devices {
+                    let type_index = device_lock.read().await.type_index;
+                    if self.is_runtime_suspended(type_index).await {
+                        continue;
+                    }
+                    if let Some(status) = statuses.get(&type_index) {
+                        self.preloaded_statuses.write().await.insert(type_index, status.clone());
                     }
-                    let device_id = device_lock.read().await.type_index;
-                    self.preloaded_statuses.write().await.insert(device_id, status.unwrap());
                 }
-            )
+            }
+            Err(err) => error!("Error getting CPU statuses: {}", err),
         }
-        join_all(futures).await;
         debug!(
             "STATUS PRELOAD Time taken for all CPU devices: {:?}",
             start_update.elapsed()
@@ -234,7 +585,6 @@ impl Repository for CpuRepo {
 
     async fn update_statuses(&self) -> Result<()> {
         let start_update = Instant::now();
-        // current only supports one device:
         for device_lock in &self.devices {
             let preloaded_statuses_map = self.preloaded_statuses.read().await;
             let preloaded_statuses = preloaded_statuses_map.get(&device_lock.read().await.type_index);
@@ -0,0 +1,198 @@
+/*
+ * CoolerControl - monitor and control your cooling and other devices
+ * Copyright (c) 2021-2024  Guy Boldon, Eren Simsek and contributors
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use log::{debug, info};
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use crate::device::{ChannelStatus, Device, DeviceInfo, DeviceType, Status, TempStatus, UID};
+use crate::repositories::repository::{DeviceList, Repository};
+use crate::setting::Setting;
+
+const MOCK_TEMP_NAME: &str = "Mock Temp";
+const MOCK_FAN_NAME: &str = "Mock Fan";
+const MOCK_LED_NAME: &str = "Mock LED";
+/// One full sine wave cycle, so a demo/test run can observe the whole temp range without a long
+/// wait.
+const TEMP_CYCLE_SECONDS: f64 = 60.0;
+const TEMP_BASELINE_CELSIUS: f64 = 45.0;
+const TEMP_AMPLITUDE_CELSIUS: f64 = 20.0;
+const DEFAULT_FAN_DUTY: u8 = 50;
+
+/// Per-device state the mock maintains between polls: a deterministic sine-wave temperature, and
+/// whatever duty/lighting was last applied via `apply_setting`, so the pipeline can be exercised
+/// end-to-end without touching real hardware.
+struct VirtualDeviceState {
+    started_at: Instant,
+    applied_duty: RwLock<u8>,
+    applied_colors: RwLock<Vec<(u8, u8, u8)>>,
+}
+
+impl VirtualDeviceState {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            applied_duty: RwLock::new(DEFAULT_FAN_DUTY),
+            applied_colors: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// A deterministic sine/ramp curve so runs are reproducible across daemon restarts within
+    /// the same process uptime, for CI and bug reports.
+    fn current_temp(&self) -> f64 {
+        let elapsed_secs = self.started_at.elapsed().as_secs_f64();
+        let phase = (elapsed_secs % TEMP_CYCLE_SECONDS) / TEMP_CYCLE_SECONDS;
+        TEMP_BASELINE_CELSIUS + TEMP_AMPLITUDE_CELSIUS * (2.0 * PI * phase).sin()
+    }
+}
+
+/// A synthetic `Repository` for development and headless demos: each virtual device exposes a
+/// sine-wave temperature, a fan channel whose `apply_setting` actually stores the requested duty
+/// and reflects it back in `Status`, and a color channel whose `apply_setting` stores the
+/// requested colors; those colors are not reported in `Status`. Unlike
+/// `CpuRepo`/`GpuRepo`, nothing here touches real hardware, so the whole profile/function
+/// pipeline is testable without it.
+pub struct MockRepo {
+    devices: DeviceList,
+    virtual_device_states: HashMap<u8, VirtualDeviceState>,
+    mock_device_count: u8,
+}
+
+impl MockRepo {
+    pub async fn new(mock_device_count: u8) -> Result<Self> {
+        Ok(Self {
+            devices: vec![],
+            virtual_device_states: HashMap::new(),
+            mock_device_count,
+        })
+    }
+
+    async fn request_status(&self, type_index: u8) -> (Vec<ChannelStatus>, Vec<TempStatus>) {
+        let Some(state) = self.virtual_device_states.get(&type_index) else {
+            return (vec![], vec![]);
+        };
+        let applied_duty = *state.applied_duty.read().await;
+        let channels = vec![ChannelStatus {
+            name: MOCK_FAN_NAME.to_string(),
+            rpm: Some(500 + u32::from(applied_duty) * 15),
+            duty: Some(f64::from(applied_duty)),
+            pwm_mode: None,
+        }];
+        let temps = vec![TempStatus {
+            name: MOCK_TEMP_NAME.to_string(),
+            temp: state.current_temp(),
+            frontend_name: MOCK_TEMP_NAME.to_string(),
+            external_name: MOCK_TEMP_NAME.to_string(),
+        }];
+        (channels, temps)
+    }
+}
+
+#[async_trait]
+impl Repository for MockRepo {
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Custom
+    }
+
+    async fn initialize_devices(&mut self) -> Result<()> {
+        debug!("Starting Mock Device Initialization for {} device(s)", self.mock_device_count);
+        for type_index in 1..=self.mock_device_count {
+            self.virtual_device_states.insert(type_index, VirtualDeviceState::new());
+            let (channels, temps) = self.request_status(type_index).await;
+            let status = Status {
+                channels,
+                temps,
+                ..Default::default()
+            };
+            let device = Device::new(
+                format!("Mock Device {type_index}"),
+                DeviceType::Custom,
+                type_index,
+                None,
+                Some(DeviceInfo {
+                    temp_max: 100,
+                    temp_ext_available: true,
+                    ..Default::default()
+                }),
+                Some(status),
+                None,
+            );
+            self.devices.push(Arc::new(RwLock::new(device)));
+        }
+        info!("Mock Repository initialized with {} virtual device(s)", self.mock_device_count);
+        Ok(())
+    }
+
+    async fn devices(&self) -> DeviceList {
+        self.devices.iter().cloned().collect()
+    }
+
+    async fn preload_statuses(&self) {
+        // Deterministic and cheap to compute; nothing to preload ahead of `update_statuses`.
+    }
+
+    async fn update_statuses(&self) -> Result<()> {
+        for device_lock in &self.devices {
+            let type_index = device_lock.read().await.type_index;
+            let (channels, temps) = self.request_status(type_index).await;
+            let status = Status {
+                channels,
+                temps,
+                ..Default::default()
+            };
+            device_lock.write().await.set_status(status);
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        info!("Mock Repository shutdown");
+        Ok(())
+    }
+
+    async fn apply_setting(&self, device_uid: &UID, setting: &Setting) -> Result<()> {
+        let type_index = device_uid
+            .parse::<u8>()
+            .map_err(|_| anyhow!("Mock device uid should be its type_index: {device_uid}"))?;
+        let state = self
+            .virtual_device_states
+            .get(&type_index)
+            .ok_or_else(|| anyhow!("Unknown mock device: {device_uid}"))?;
+        if setting.channel_name == MOCK_FAN_NAME {
+            if let Some(duty) = setting.speed_fixed {
+                *state.applied_duty.write().await = duty;
+                return Ok(());
+            }
+        } else if setting.channel_name == MOCK_LED_NAME {
+            if let Some(lighting) = &setting.lighting {
+                *state.applied_colors.write().await = lighting.colors.clone();
+                return Ok(());
+            }
+        }
+        Err(anyhow!(
+            "Unsupported mock setting for channel: {}",
+            setting.channel_name
+        ))
+    }
+}
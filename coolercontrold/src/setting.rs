@@ -17,6 +17,7 @@
  */
 
 
+use std::collections::HashMap;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
@@ -26,7 +27,7 @@ use crate::device::UID;
 
 /// Setting is a passed struct used to apply various settings to a specific device.
 /// Usually only one specific lighting or speed setting is applied at a time.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Setting {
     pub channel_name: String,
 
@@ -68,7 +69,7 @@ impl Default for Setting {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LightingSettings {
     /// The lighting mode name
     pub mode: String,
@@ -81,9 +82,80 @@ pub struct LightingSettings {
 
     /// a list of RGB tuple values, eg [(20,20,120), (0,0,255)]
     pub colors: Vec<(u8, u8, u8)>,
+
+    /// The temperature source driving `temp_reactive`. Ignored otherwise.
+    pub temp_source: Option<TempSource>,
+
+    /// The RGB color to show at `temp_min` and below, for `temp_reactive` mode.
+    pub gradient_cold_color: Option<(u8, u8, u8)>,
+
+    /// The RGB color to show at `temp_max` and above, for `temp_reactive` mode.
+    pub gradient_hot_color: Option<(u8, u8, u8)>,
+
+    /// The temperature, in degrees, mapped to `gradient_cold_color`.
+    pub temp_min: Option<f64>,
+
+    /// The temperature, in degrees, mapped to `gradient_hot_color`.
+    pub temp_max: Option<f64>,
+
+    /// A color temperature in Kelvin (1000-40000), used as a single warm/cool knob in place of
+    /// an explicit RGB color. When set, `colors` is derived from this via [`kelvin_to_rgb`] at
+    /// write/apply time, so downstream device repositories only ever need to look at `colors`.
+    pub temperature_k: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Linearly interpolates between `cold` and `hot` based on where `temp` falls within
+/// `[temp_min, temp_max]`, clamping the normalized position to `[0, 1]` so readings outside the
+/// range hold at the nearest endpoint color rather than extrapolating past it.
+pub fn interpolate_gradient_color(
+    cold: (u8, u8, u8),
+    hot: (u8, u8, u8),
+    temp: f64,
+    temp_min: f64,
+    temp_max: f64,
+) -> (u8, u8, u8) {
+    let span = temp_max - temp_min;
+    let factor = if span <= 0.0 {
+        0.0
+    } else {
+        ((temp - temp_min) / span).clamp(0.0, 1.0)
+    };
+    let channel = |cold: u8, hot: u8| {
+        (f64::from(cold) + factor * (f64::from(hot) - f64::from(cold))).round() as u8
+    };
+    (
+        channel(cold.0, hot.0),
+        channel(cold.1, hot.1),
+        channel(cold.2, hot.2),
+    )
+}
+
+/// Approximates the RGB color of a blackbody radiator at `kelvin`, clamped to `[1000, 40000]`,
+/// using the Tanner-Helland formula. This gives users a single physically meaningful
+/// warm/cool knob for "white" accent lighting instead of requiring them to guess RGB triples.
+pub fn kelvin_to_rgb(kelvin: u32) -> (u8, u8, u8) {
+    let t = f64::from(kelvin.clamp(1000, 40000)) / 100.0;
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        (329.698_727_446 * (t - 60.0).powf(-0.133_204_759_2)).clamp(0.0, 255.0)
+    };
+    let green = if t <= 66.0 {
+        (99.470_802_586_1 * t.ln() - 161.119_568_166_1).clamp(0.0, 255.0)
+    } else {
+        (288.122_169_528_3 * (t - 60.0).powf(-0.075_514_849_2)).clamp(0.0, 255.0)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        (138.517_731_223_1 * (t - 10.0).ln() - 305.044_792_730_7).clamp(0.0, 255.0)
+    };
+    (red.round() as u8, green.round() as u8, blue.round() as u8)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TempSource {
     /// The internal name for this Temperature Source. Not the frontend_name or external_name
     pub temp_name: String,
@@ -92,7 +164,7 @@ pub struct TempSource {
     pub device_uid: UID,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LcdSettings {
     /// The Lcd mode name
     pub mode: String,
@@ -111,6 +183,10 @@ pub struct LcdSettings {
 
     /// a list of RGB tuple values, eg [(20,20,120), (0,0,255)]
     pub colors: Vec<(u8, u8, u8)>,
+
+    /// The temperature source driving an LCD temp display, separate from the channel's own
+    /// `temp_source` (which drives fan speed).
+    pub temp_source: Option<TempSource>,
 }
 
 /// General Settings for CoolerControl
@@ -122,12 +198,144 @@ pub struct CoolerControlSettings {
     pub startup_delay: Duration,
     pub smoothing_level: u8,
     pub thinkpad_full_speed: bool,
+
+    /// When true (the default), a device whose sysfs `power/runtime_status` reports
+    /// `suspended` is skipped during polling and its last cached `Status` is reused, instead of
+    /// forcing it out of runtime suspend just to take a reading. Disable for continuous readings
+    /// at the cost of idle power draw.
+    pub power_aware_polling: bool,
+
+    /// The authentication mode used to protect the HTTP API. Defaults to `Auth::None`, which
+    /// preserves the previous, unauthenticated behavior for local-only setups.
+    pub auth: Auth,
+
+    /// The unit temperatures are reported in, and `speed_profile` temp points are interpreted
+    /// in, at the API boundary. Internal storage and profile math always stay Celsius.
+    pub temp_unit: TempUnit,
+
+    /// The base polling period, in milliseconds, for repos/devices that don't declare their own
+    /// `Repository::poll_period`. Defaults to 1000 (one second), matching the daemon's historical
+    /// fixed tick rate.
+    pub tick_rate_ms: u64,
+
+    /// A filter applied to every device's temp sources, on top of any per-device
+    /// `CoolerControlDeviceSettings::temp_filter`, for hiding noisy or duplicate sensors
+    /// globally.
+    pub temp_filter: Option<Filter>,
+}
+
+/// A user-facing temperature unit. Internal storage (`TempStatus`, `Profile::speed_profile`,
+/// `DeviceInfo::temp_max`/`temp_min`) always stays Celsius; conversion only happens when a value
+/// crosses the API boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString, Serialize, Deserialize)]
+pub enum TempUnit {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl Default for TempUnit {
+    fn default() -> Self {
+        Self::Celsius
+    }
+}
+
+/// Converts a Celsius value (as stored internally) to the given display unit.
+pub fn celsius_to_unit(celsius: f64, unit: TempUnit) -> f64 {
+    match unit {
+        TempUnit::Celsius => celsius,
+        TempUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        TempUnit::Kelvin => celsius + 273.15,
+    }
+}
+
+/// Converts a value given in `unit` back to Celsius, for ingesting `speed_profile` temp points
+/// supplied in the user's chosen unit.
+pub fn unit_to_celsius(value: f64, unit: TempUnit) -> f64 {
+    match unit {
+        TempUnit::Celsius => value,
+        TempUnit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+        TempUnit::Kelvin => value - 273.15,
+    }
+}
+
+/// The authentication scheme applied to every request except `/handshake` and `/login`.
+/// Secrets are never stored in plain text; only their SHA-256 hash is persisted to the
+/// configuration file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Auth {
+    /// No authentication required. This is the default, matching legacy behavior.
+    None,
+
+    /// A username paired with a hashed secret, suitable for multi-user reverse-proxy setups.
+    Credentials { user: String, secret_hash: String },
+
+    /// A single shared bearer token, suitable for a non-local UI or reverse proxy.
+    Token { secret_hash: String },
+}
+
+impl Default for Auth {
+    fn default() -> Self {
+        Self::None
+    }
 }
 
 /// General Device Settings for CoolerControl
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CoolerControlDeviceSettings {
     pub disable: bool,
+
+    /// Hides noisy or duplicate temp sensors (and, for devices, channels) from this device
+    /// without disabling the device entirely.
+    pub temp_filter: Option<Filter>,
+}
+
+/// A declarative allow-list or deny-list of names, matched literally or as a regex.
+///
+/// Whether it behaves as an allow-list or a deny-list is controlled by `is_list_ignored`:
+/// with `is_list_ignored = false`, names matching `list` are hidden and everything else is kept
+/// (a deny-list); with `is_list_ignored = true`, only names matching `list` are kept (an
+/// allow-list). An empty `list` hides nothing, so the default `Filter` is a no-op.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Filter {
+    /// Whether `list` names things to keep (`true`) or things to hide (`false`).
+    pub is_list_ignored: bool,
+
+    /// The names or patterns to match against.
+    pub list: Vec<String>,
+
+    /// Treat each `list` entry as a regular expression instead of a literal string.
+    pub regex: bool,
+
+    /// Match case-sensitively. Has no effect on a `regex` entry that sets its own flags.
+    pub case_sensitive: bool,
+
+    /// Anchor each pattern to match the entire name (`^(?:pattern)$`) rather than a substring.
+    pub whole_word: bool,
+}
+
+impl Filter {
+    /// Builds the effective regex pattern for one `list` entry, applying `regex`,
+    /// `whole_word`, and `case_sensitive` in that order.
+    fn pattern_for(&self, entry: &str) -> String {
+        let body = if self.regex { entry.to_string() } else { regex::escape(entry) };
+        let body = if self.whole_word { format!("^(?:{body})$") } else { body };
+        if self.case_sensitive { body } else { format!("(?i){body}") }
+    }
+
+    /// Returns whether `name` should be hidden: `is_list_ignored` XOR'd with whether any
+    /// pattern in `list` matches `name`. An empty `list` never matches, so an unconfigured
+    /// `Filter` hides nothing.
+    pub fn is_ignored(&self, name: &str) -> bool {
+        if self.list.is_empty() {
+            return false;
+        }
+        let patterns: Vec<String> = self.list.iter().map(|entry| self.pattern_for(entry)).collect();
+        let matches = regex::RegexSet::new(&patterns)
+            .map(|set| set.is_match(name))
+            .unwrap_or(false);
+        self.is_list_ignored ^ matches
+    }
 }
 
 /// Profile Settings
@@ -153,6 +361,25 @@ pub struct Profile {
 
     /// The function uid to apply to this profile
     pub function_uid: UID,
+
+    /// Named alternate configurations of this same Profile (e.g. "Silent", "Performance"),
+    /// keyed by variant name. The Profile's own `speed_fixed`/`speed_profile`/`temp_source`/
+    /// `function_uid` always reflect whichever variant is currently active.
+    #[serde(default)]
+    pub variants: HashMap<String, ProfileVariant>,
+
+    /// The name of the currently active variant, if any. `None` means the Profile's base
+    /// settings (above) are being used directly, with no variant switching in play.
+    #[serde(default)]
+    pub active_variant: Option<String>,
+
+    /// How to interpolate between the `speed_profile` control points. Defaults to `Linear` for
+    /// backward compatibility with existing profiles.
+    #[serde(default)]
+    pub interpolation: ProfileInterpolation,
+
+    /// The steepness `k` used by `ProfileInterpolation::Logarithmic`. Ignored otherwise.
+    pub steepness: Option<f64>,
 }
 
 impl Default for Profile {
@@ -165,8 +392,179 @@ impl Default for Profile {
             speed_profile: None,
             temp_source: None,
             function_uid: "0".to_string(),
+            variants: HashMap::new(),
+            active_variant: None,
+            interpolation: ProfileInterpolation::Linear,
+            steepness: None,
+        }
+    }
+}
+
+/// The curve used to interpolate duty between a Profile's `speed_profile` control points.
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString, Serialize, Deserialize)]
+pub enum ProfileInterpolation {
+    /// Straight line between each pair of neighboring control points. The original behavior.
+    Linear,
+
+    /// A natural cubic spline through all control points, giving a smooth, C² continuous
+    /// response. On any segment where the spline would leave the two neighboring points' duty
+    /// range, and whenever there are fewer than 3 points, the segment is interpolated linearly
+    /// instead; no Hermite slopes are computed.
+    Cubic,
+
+    /// Maps the normalized position within a segment through `ln(1 + x·(e^k−1))/k` before
+    /// lerping duty, which ramps up steeply near the low end of the segment and flattens toward
+    /// the high end. Uses
+    /// `Profile::steepness` for `k`.
+    Logarithmic,
+}
+
+impl Default for ProfileInterpolation {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+/// The default steepness used by `ProfileInterpolation::Logarithmic` when a Profile doesn't
+/// specify one.
+const DEFAULT_LOG_STEEPNESS: f64 = 4.0;
+
+/// Evaluates a Profile's `speed_profile` control points at the given `temp`, using the curve
+/// selected by `interpolation`. `points` must be sorted by temperature ascending, as is the case
+/// for every `Profile::speed_profile`. Returns `None` if `points` is empty.
+///
+/// Not wired up in this trimmed snapshot: the `Graph` Profile duty evaluation this would replace
+/// lives in `processing/settings.rs` (`SettingsController`), which isn't present in this
+/// checkout, so there's no real call site to thread `interpolation`/`steepness` through yet.
+/// Covered by unit tests below instead.
+pub fn interpolate_duty(
+    points: &[(f64, u8)],
+    temp: f64,
+    interpolation: ProfileInterpolation,
+    steepness: Option<f64>,
+) -> Option<u8> {
+    if points.is_empty() {
+        return None;
+    }
+    if temp <= points[0].0 {
+        return Some(points[0].1);
+    }
+    if temp >= points[points.len() - 1].0 {
+        return Some(points[points.len() - 1].1);
+    }
+    let segment_index = points
+        .windows(2)
+        .position(|pair| temp >= pair[0].0 && temp <= pair[1].0)?;
+    let (temp_lo, duty_lo) = points[segment_index];
+    let (temp_hi, duty_hi) = points[segment_index + 1];
+    let span = temp_hi - temp_lo;
+    if span <= 0.0 {
+        return Some(duty_lo);
+    }
+    let x = (temp - temp_lo) / span;
+    let duty = match interpolation {
+        ProfileInterpolation::Linear => lerp(duty_lo, duty_hi, x),
+        ProfileInterpolation::Cubic => {
+            cubic_spline_duty(points, segment_index, x).unwrap_or_else(|| lerp(duty_lo, duty_hi, x))
+        }
+        ProfileInterpolation::Logarithmic => {
+            let k = steepness.unwrap_or(DEFAULT_LOG_STEEPNESS);
+            lerp(duty_lo, duty_hi, logarithmic_ease(x, k))
+        }
+    };
+    Some(duty.round().clamp(0.0, u8::MAX as f64) as u8)
+}
+
+fn lerp(duty_lo: u8, duty_hi: u8, x: f64) -> f64 {
+    f64::from(duty_lo) + (f64::from(duty_hi) - f64::from(duty_lo)) * x
+}
+
+/// `y = ln(1 + x·(e^k−1)) / k`, the audio-style log curve. `k <= 0` degrades to a linear ramp.
+fn logarithmic_ease(x: f64, k: f64) -> f64 {
+    if k <= 0.0 {
+        return x;
+    }
+    (1.0 + x * (k.exp() - 1.0)).ln() / k
+}
+
+/// Evaluates the natural cubic spline through `points` at normalized position `x` within
+/// `segment_index`. Returns `None` (falling back to linear) when the value would leave the
+/// neighboring control points' duty range, or when fewer than 3 points are available, since a
+/// spline needs at least one interior point to be meaningful.
+fn cubic_spline_duty(points: &[(f64, u8)], segment_index: usize, x: f64) -> Option<f64> {
+    let second_derivatives = natural_cubic_second_derivatives(points)?;
+    let (temp_lo, duty_lo) = points[segment_index];
+    let (temp_hi, duty_hi) = points[segment_index + 1];
+    let delta = temp_hi - temp_lo;
+    let t = temp_lo + x * delta;
+    let a = (temp_hi - t) / delta;
+    let b = (t - temp_lo) / delta;
+    let y = a * f64::from(duty_lo)
+        + b * f64::from(duty_hi)
+        + ((a.powi(3) - a) * second_derivatives[segment_index]
+            + (b.powi(3) - b) * second_derivatives[segment_index + 1])
+            * (delta * delta)
+            / 6.0;
+    let (range_min, range_max) = if duty_lo <= duty_hi {
+        (duty_lo, duty_hi)
+    } else {
+        (duty_hi, duty_lo)
+    };
+    if y < f64::from(range_min) || y > f64::from(range_max) {
+        None
+    } else {
+        Some(y)
+    }
+}
+
+/// Solves the tridiagonal system for a natural cubic spline's second derivatives `M_i` (boundary
+/// second derivatives fixed to zero). Returns `None` for fewer than 3 points.
+fn natural_cubic_second_derivatives(points: &[(f64, u8)]) -> Option<Vec<f64>> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let mut diag = vec![0.0; n];
+    let mut upper = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+    diag[0] = 1.0;
+    diag[n - 1] = 1.0;
+    for i in 1..n - 1 {
+        let (temp_prev, duty_prev) = points[i - 1];
+        let (temp_curr, duty_curr) = points[i];
+        let (temp_next, duty_next) = points[i + 1];
+        let h_prev = temp_curr - temp_prev;
+        let h_next = temp_next - temp_curr;
+        if h_prev <= 0.0 || h_next <= 0.0 {
+            return None;
         }
+        diag[i] = 2.0 * (h_prev + h_next);
+        upper[i] = h_next;
+        rhs[i] = 6.0
+            * ((f64::from(duty_next) - f64::from(duty_curr)) / h_next
+                - (f64::from(duty_curr) - f64::from(duty_prev)) / h_prev);
+        let lower = h_prev;
+        let factor = lower / diag[i - 1];
+        diag[i] -= factor * upper[i - 1];
+        rhs[i] -= factor * rhs[i - 1];
+    }
+    let mut second_derivatives = vec![0.0; n];
+    second_derivatives[n - 1] = 0.0;
+    for i in (1..n - 1).rev() {
+        second_derivatives[i] = (rhs[i] - upper[i] * second_derivatives[i + 1]) / diag[i];
     }
+    second_derivatives[0] = 0.0;
+    Some(second_derivatives)
+}
+
+/// A named, switchable set of speed settings for a Profile. See [`Profile::variants`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileVariant {
+    pub name: String,
+    pub speed_fixed: Option<u8>,
+    pub speed_profile: Option<Vec<(f64, u8)>>,
+    pub temp_source: Option<TempSource>,
+    pub function_uid: UID,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Display, EnumString, Serialize, Deserialize)]
@@ -218,3 +616,65 @@ pub enum FunctionType {
     SimpleMovingAvg,
     ExponentialMovingAvg,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolate_duty_returns_none_for_empty_points() {
+        assert_eq!(interpolate_duty(&[], 50.0, ProfileInterpolation::Linear, None), None);
+    }
+
+    #[test]
+    fn interpolate_duty_clamps_to_the_end_points_outside_their_range() {
+        let points = [(20.0, 10_u8), (80.0, 100_u8)];
+        assert_eq!(interpolate_duty(&points, 0.0, ProfileInterpolation::Linear, None), Some(10));
+        assert_eq!(interpolate_duty(&points, 100.0, ProfileInterpolation::Linear, None), Some(100));
+    }
+
+    #[test]
+    fn interpolate_duty_linear_is_a_straight_line_between_points() {
+        let points = [(20.0, 10_u8), (80.0, 100_u8)];
+        assert_eq!(interpolate_duty(&points, 50.0, ProfileInterpolation::Linear, None), Some(55));
+    }
+
+    #[test]
+    fn interpolate_duty_logarithmic_matches_linear_at_the_segment_endpoints() {
+        let points = [(20.0, 10_u8), (80.0, 100_u8)];
+        assert_eq!(
+            interpolate_duty(&points, 20.0, ProfileInterpolation::Logarithmic, Some(4.0)),
+            Some(10)
+        );
+        assert_eq!(
+            interpolate_duty(&points, 80.0, ProfileInterpolation::Logarithmic, Some(4.0)),
+            Some(100)
+        );
+    }
+
+    #[test]
+    fn interpolate_duty_cubic_falls_back_to_linear_with_fewer_than_three_points() {
+        let points = [(20.0, 10_u8), (80.0, 100_u8)];
+        assert_eq!(
+            interpolate_duty(&points, 50.0, ProfileInterpolation::Cubic, None),
+            interpolate_duty(&points, 50.0, ProfileInterpolation::Linear, None)
+        );
+    }
+
+    #[test]
+    fn interpolate_duty_cubic_passes_through_every_control_point() {
+        let points = [(20.0, 10_u8), (40.0, 30_u8), (60.0, 60_u8), (80.0, 100_u8)];
+        for &(temp, duty) in &points {
+            assert_eq!(
+                interpolate_duty(&points, temp, ProfileInterpolation::Cubic, None),
+                Some(duty)
+            );
+        }
+    }
+
+    #[test]
+    fn logarithmic_ease_degrades_to_linear_for_non_positive_k() {
+        assert_eq!(logarithmic_ease(0.3, 0.0), 0.3);
+        assert_eq!(logarithmic_ease(0.3, -1.0), 0.3);
+    }
+}